@@ -68,6 +68,7 @@ map-id-wrong = Map ID incorrect.
 no-positions = No marker positions provided.
 validation-fail = Validation failed due to:
 filename-empty = No filename provided.
+filename-invalid = The filename can't contain folders or "..".
 count = Count
 actions = Actions
 
@@ -77,6 +78,7 @@ primary-window-toggle = Taimi Window Toggle
 timer-window-toggle = Timer Window Toggle
 primary-window-toggle-text = Show/hide taimi primary window
 timer-key-trigger = Timer Key Trigger { $id }
+trace-record-toggle = Trace Recording Toggle
 
 ## Config
 
@@ -84,10 +86,19 @@ config-tab = Config
 stock-imgui-progress-bar = Stock Imgui Progress Bar
 shadow = Shadow
 centre-text-after-icon = Centre text after icon
+max-text-alerts = Max text alerts on screen
+remaining-format = Time remaining format
 imgui-notice = You can control-click on a slider element, or such, to be able to directly input data to it. Remember to press enter after inputting the value.
 marker-trigger = Marker set position trigger behaviour
 marker-condition = Behaviour condition
 autoplace-warning = If you do not have RTAPI installed, we will not be able to detect whether you are a lieutenant instead of just a commander.
+state-api = State API
+state-api-description = Publishes the current map, phases, upcoming alerts and text alerts as JSON for stream overlays. Only programs on this computer can connect.
+enable-state-api = Enable state API
+state-api-port = Port
+state-api-allowed-origins = Allowed web pages
+state-api-allowed-origins-tooltip = Comma separated origins, such as https://example.com, whose pages may connect over WebSocket. Other web pages are refused.
+state-api-address = Listening on ws://127.0.0.1:{ $port } and http://127.0.0.1:{ $port }
 
 ## Windows
 
@@ -156,6 +167,8 @@ marker-set = { marker } Set
 marker-set-create = Create { marker-set }
 marker-set-edit = Edit { marker-set }
 marker-set-delete = Delete { marker-set }
+marker-file-convert = Convert File To
+marker-file-convert-explanation = Rewrites the whole file this { marker-set } is in as another format. Anything the new format has no place for is kept, so it can be converted back.
 scaling-factor = scaling factor
 current-scaling-factor = Current { scaling-factor }: ({ $x }, { $y })
 current-scaling-factor-multiple = Current { scaling-factor } as multiple of ft per continent unit: ({ $x }, { $y })
@@ -174,10 +187,37 @@ no-markers-for-map = No markers found for current map.
 cant-place-markers = Can't place
 autoplacement-disable = Disable auto-placement
 autoplacement-enable = Enable auto-placement
+agent-presets = Agent { markers }
+agent-presets-description = Presets give squad members agent markers by account, role or subgroup. Taimi can't target anyone itself, so target each member as asked and apply their marker with the button or keybind.
+no-agent-presets = No presets in { $file }.
+agent-preset = Preset
+agent-preset-start = Start
+agent-preset-stop = Stop
+agent-preset-apply = Apply to target
+agent-preset-skip = Skip
+agent-preset-target = Target { $member }
+agent-preset-targeting = Currently targeting { $account }
+agent-preset-wrong-target = Currently targeting { $account }, so their marker won't be applied
+agent-preset-target-unknown = ArcDPS hasn't said who is targeted, so check the target yourself
+agent-preset-finished = Every matched member has been given their marker.
+agent-preset-unmatched = Nobody matches
+clear-agent-markers = { clear } agent { markers }
+member-header = Member
+assigned-by-header = Given to
+subgroup-arg = Subgroup { $subgroup }
+agent-marker-next = Apply Next Agent Marker
 
 ## Markers window
 clear-markers = { clear } { markers }
 clear-spent-autoplace = Reset spent auto-placement
+autoplace = Auto-placement
+autoplace-armed = Armed
+autoplace-spent = Spent
+autoplace-rearm = Re-arm
+rearm-never = Re-arms on map change
+rearm-on-leave = Re-arms { $distance } units outside the trigger
+rearm-cooldown = Re-arms after { $seconds }s
+rearm-combat-exit = Re-arms after combat
 
 ## Edit markers window
 
@@ -196,16 +236,94 @@ manual-position = Manual { position }
 set-manually-save = { save } manual { position }
 trigger-explanation = A trigger for a marker set is a 15m radius sphere with its centre at the trigger location.
 
+## Edit timer window
+
+edit-timer = Edit timer
+map-ids = Map { id }s
+text-alert-priority = Text alert priority
+map-id-invalid = "{ $id }" is not a valid map { id }.
+id-empty = { id } is empty.
+no-phases = The timer has no phases.
+timer-reset = Reset trigger
+phase-arg = Phase: { $name }
+phase-default-name = Phase { $index }
+add-phase = Add phase
+remove-phase = Remove phase
+phase-start = Start trigger
+phase-has-finish = Has a finish trigger
+phase-finish = Finish trigger
+trigger-type = Trigger type
+add-trigger = Add trigger
+remove-trigger = Remove trigger
+key-bind = Key bind
+key-bind-invalid = "{ $key }" is not a valid key bind; use 0 to 4.
+elapsed = Elapsed seconds
+skill-id = Skill { id }
+species-id = Species { id }
+on-self = On self
+threshold = Threshold
+radius = Radius
+antipode = Antipode
+require-entry = Require entry
+require-departure = Require departure
+require-combat = Require combat
+require-out-of-combat = Require out of combat
+alerts-arg = Alerts: { $count }
+add-alert = Add alert
+remove-alert = Remove alert
+alert = Alert
+alert-duration = Alert duration
+warning = Warning
+warning-duration = Warning duration
+timestamps = Timestamps
+timestamp-invalid = "{ $timestamp }" is not a valid timestamp.
+add-marker = Add marker
+remove-marker = Remove marker
+texture = Texture
+size = Size
+duration = Duration
+marker-no-position = A marker has no { position }.
+save-timer-explanation = The timer will be saved to "{ $filename }" in the ad-hoc folder.
+
 ## Timer tab
 
 reload-timers = Reload { timers }
+new-timer = New timer
+edit-timer-button = Edit
 timer-tab = { timers }
 source-arg = Source: { $source }
 source-adhoc = Source: Ad-hoc
 select-a-timer = Please select a timer to configure!
+trace-record-start = Record trace
+trace-record-stop = Stop recording trace
+trace-replay = Replay latest trace
+trace-replaying = Replaying trace...
+overrides = Customise alerts
+timer-offset = Offset all alerts
+alert-override = { $phase }: { $text }
+mute-alert = Mute alert
+mute-warning = Mute warning
+offset = Offset
+alert-text = Alert text
+warning-text = Warning text
+alert-colour = Alert colour
+warning-colour = Warning colour
+fill-colour = Fill colour
+reset-overrides = { reset } customisations
+attempts = Attempts
+no-attempts = No attempts recorded yet.
+attempts-summary = { $attempts } attempts, { $finished } finished ({ $rate }% success)
+attempts-best = Personal best: { $best }, median: { $median }
+best = Best
+median = Median
+resets = Resets
+clear-attempts = Clear attempts
 
 ## Timer window
 
 timer-window = { timers }
 no-phases-active = No phases currently active, no timers running.
+upcoming-alerts = Upcoming alerts
+upcoming-alert = { $message } (in { $remaining }s)
 reset-timers = { reset } { timers }
+direction-distance = { $name } ({ $distance })
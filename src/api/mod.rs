@@ -0,0 +1,50 @@
+pub mod server;
+pub mod snapshot;
+
+#[allow(unused_imports)]
+pub use {
+    server::ApiServer,
+    snapshot::{AlertSnapshot, ApiSnapshot, ApiState, PhaseSnapshot, TextAlertSnapshot},
+};
+
+use {
+    crate::timer::{AlertPlayer, PhaseState, TextAlert, TimerFile},
+    std::sync::Arc,
+    tokio::sync::mpsc::{Receiver, Sender},
+};
+
+pub enum ApiEvent {
+    PhaseFeed(PhaseState),
+    PhaseReset(Arc<TimerFile>),
+    AlertStart(TextAlert),
+    AlertEnd(Arc<TimerFile>),
+    Map(u32),
+    Player(AlertPlayer),
+    Configure {
+        enabled: bool,
+        port: u16,
+        allowed_origins: Vec<String>,
+    },
+}
+
+pub fn load(receiver: Receiver<ApiEvent>) {
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(error) => {
+            log::error!("Error! {}", error);
+            return;
+        }
+    };
+    rt.block_on(ApiServer::new().run(receiver));
+}
+
+pub fn sender() -> Option<Sender<ApiEvent>> {
+    crate::API_SENDER
+        .try_read()
+        .as_ref()
+        .ok()
+        .and_then(|s| (*s).clone())
+}
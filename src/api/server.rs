@@ -0,0 +1,373 @@
+use {
+    super::{ApiEvent, ApiState},
+    futures::{SinkExt, StreamExt},
+    std::{
+        net::{Ipv4Addr, SocketAddr},
+        sync::Arc,
+    },
+    tokio::{
+        io::AsyncWriteExt,
+        net::{TcpListener, TcpStream},
+        sync::{mpsc::Receiver, watch},
+        task::{JoinHandle, JoinSet},
+        time::{interval, timeout, Duration, Instant},
+    },
+    tokio_tungstenite::tungstenite::{
+        handshake::server::{ErrorResponse, Request, Response},
+        http::{header::ORIGIN, StatusCode},
+        Message,
+    },
+};
+
+/// How often remaining times are brought up to date for clients.
+const UPDATE_INTERVAL: Duration = Duration::from_millis(250);
+/// How long a client has to finish sending its request headers.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_SIZE: usize = 8192;
+
+/**
+ * Publishes the timer state to local clients as JSON.
+ *
+ * WebSocket clients are sent the whole state whenever it changes, and any other HTTP
+ * request is answered with the state as it is right then. Only 127.0.0.1 is listened
+ * on, and nothing is listened on at all until the API is enabled in the settings.
+ *
+ * Browsers let any page open a WebSocket to localhost, so handshakes from a web page are
+ * refused unless its origin has been allowed in the settings.
+ */
+pub struct ApiServer {
+    state: ApiState,
+    snapshot: watch::Sender<String>,
+    listener: Option<(u16, Arc<[String]>, JoinHandle<()>)>,
+}
+
+impl ApiServer {
+    pub fn new() -> Self {
+        let (snapshot, _) = watch::channel(String::new());
+        Self {
+            state: Default::default(),
+            snapshot,
+            listener: None,
+        }
+    }
+
+    fn configure(&mut self, enabled: bool, port: u16, allowed_origins: Vec<String>) {
+        let unchanged = self
+            .listener
+            .as_ref()
+            .is_some_and(|(current, origins, _)| *current == port && **origins == *allowed_origins);
+        if enabled && unchanged {
+            return;
+        }
+        if let Some((port, _, task)) = self.listener.take() {
+            log::info!("No longer serving the timer state on port {port}");
+            task.abort();
+        }
+        if enabled {
+            let allowed_origins: Arc<[String]> = allowed_origins.into();
+            let snapshot = self.snapshot.subscribe();
+            let task = tokio::spawn(listen(port, snapshot, allowed_origins.clone()));
+            self.listener = Some((port, allowed_origins, task));
+        }
+    }
+
+    fn handle_event(&mut self, event: ApiEvent, now: Instant) {
+        use ApiEvent::*;
+        match event {
+            PhaseFeed(phase_state) => self.state.feed(phase_state),
+            PhaseReset(timer) => self.state.reset(&timer),
+            AlertStart(alert) => self.state.alert_start(alert, now),
+            AlertEnd(timer) => self.state.alert_end(&timer),
+            Map(map_id) => self.state.set_map(map_id),
+            Player(player) => self.state.set_player(player),
+            Configure {
+                enabled,
+                port,
+                allowed_origins,
+            } => self.configure(enabled, port, allowed_origins),
+        }
+    }
+
+    fn publish(&mut self, now: Instant) {
+        let snapshot = match serde_json::to_string(&self.state.snapshot(now)) {
+            Ok(snapshot) => snapshot,
+            Err(err) => {
+                log::error!("Couldn't serialize the timer state: {err}");
+                return;
+            }
+        };
+        self.snapshot.send_if_modified(|current| {
+            let modified = *current != snapshot;
+            *current = snapshot;
+            modified
+        });
+    }
+
+    pub async fn run(mut self, mut receiver: Receiver<ApiEvent>) {
+        let mut update_interval = interval(UPDATE_INTERVAL);
+        loop {
+            tokio::select! {
+                evt = receiver.recv() => match evt {
+                    Some(evt) => {
+                        let now = Instant::now();
+                        self.handle_event(evt, now);
+                        self.publish(now);
+                    },
+                    None => break,
+                },
+                _ = update_interval.tick() => self.publish(Instant::now()),
+            }
+        }
+        if let Some((_, _, task)) = self.listener.take() {
+            task.abort();
+        }
+    }
+}
+
+async fn listen(port: u16, snapshot: watch::Receiver<String>, allowed_origins: Arc<[String]>) {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = match TcpListener::bind(address).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::error!("Couldn't serve the timer state on {address}: {err}");
+            return;
+        }
+    };
+    log::info!("Serving the timer state on {address}");
+    accept(listener, snapshot, allowed_origins).await;
+}
+
+/// Accepts clients until aborted, which also disconnects every client it accepted.
+async fn accept(
+    listener: TcpListener,
+    snapshot: watch::Receiver<String>,
+    allowed_origins: Arc<[String]>,
+) {
+    let mut clients = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    let snapshot = snapshot.clone();
+                    let allowed_origins = allowed_origins.clone();
+                    clients.spawn(async move {
+                        if let Err(err) = serve(stream, snapshot, &allowed_origins).await {
+                            log::debug!("Timer state client {peer} went away: {err}");
+                        }
+                    });
+                },
+                Err(err) => log::warn!("Couldn't accept a timer state client: {err}"),
+            },
+            Some(_) = clients.join_next() => (),
+        }
+    }
+}
+
+/// Reads ahead to the end of the request headers without consuming them, for the WebSocket handshake to use.
+async fn peek_request(stream: &TcpStream) -> anyhow::Result<String> {
+    let mut buf = vec![0; MAX_REQUEST_SIZE];
+    loop {
+        let read = stream.peek(&mut buf).await?;
+        if read == 0 {
+            anyhow::bail!("connection closed before sending a request");
+        }
+        let request = String::from_utf8_lossy(&buf[..read]);
+        if request.contains("\r\n\r\n") {
+            return Ok(request.into_owned());
+        }
+        if read == buf.len() {
+            anyhow::bail!("request headers are too large");
+        }
+        // peek returns immediately with whatever is buffered, so give the rest a moment
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Lets through handshakes with no origin, as from a program, or from an allowed web page.
+#[allow(clippy::result_large_err)]
+fn check_origin(
+    allowed_origins: &[String],
+    request: &Request,
+    response: Response,
+) -> Result<Response, ErrorResponse> {
+    let origin = request
+        .headers()
+        .get(ORIGIN)
+        .map(|origin| origin.to_str().unwrap_or_default());
+    match origin {
+        None | Some("null") => Ok(response),
+        Some(origin) if allowed_origins.iter().any(|allowed| allowed == origin) => Ok(response),
+        Some(origin) => {
+            log::warn!("Refused a timer state WebSocket from the origin {origin:?}");
+            let mut refusal = ErrorResponse::new(Some("Origin not allowed".to_string()));
+            *refusal.status_mut() = StatusCode::FORBIDDEN;
+            Err(refusal)
+        }
+    }
+}
+
+#[allow(clippy::result_large_err)]
+async fn serve(
+    mut stream: TcpStream,
+    mut snapshot: watch::Receiver<String>,
+    allowed_origins: &[String],
+) -> anyhow::Result<()> {
+    let request = timeout(REQUEST_TIMEOUT, peek_request(&stream)).await??;
+    let is_websocket = request.lines().any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("upgrade")
+                && value.trim().eq_ignore_ascii_case("websocket")
+        })
+    });
+
+    if !is_websocket {
+        let body = snapshot.borrow_and_update().clone();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await?;
+        return Ok(());
+    }
+
+    let mut websocket =
+        tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response| {
+            check_origin(allowed_origins, request, response)
+        })
+        .await?;
+    let current = snapshot.borrow_and_update().clone();
+    websocket.send(Message::text(current)).await?;
+    loop {
+        tokio::select! {
+            changed = snapshot.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let current = snapshot.borrow_and_update().clone();
+                websocket.send(Message::text(current)).await?;
+            },
+            // clients have nothing to say, but reading is what answers pings and notices closes
+            message = websocket.next() => match message {
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => (),
+                Some(Err(err)) => return Err(err.into()),
+            },
+        }
+    }
+    let _ = websocket.close(None).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::Value,
+        tokio::io::AsyncReadExt,
+        tokio_tungstenite::{
+            tungstenite::{client::IntoClientRequest, http::HeaderValue, Error},
+            WebSocketStream,
+        },
+    };
+
+    const ALLOWED_ORIGIN: &str = "https://overlay.example";
+
+    /// Serves `server`'s snapshot on an ephemeral port, returning its address.
+    async fn serve_ephemeral(server: &ApiServer) -> SocketAddr {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let allowed_origins: Arc<[String]> = [ALLOWED_ORIGIN.to_string()].into();
+        tokio::spawn(accept(
+            listener,
+            server.snapshot.subscribe(),
+            allowed_origins,
+        ));
+        address
+    }
+
+    fn set_map(server: &mut ApiServer, map_id: u32) {
+        let now = Instant::now();
+        server.handle_event(ApiEvent::Map(map_id), now);
+        server.publish(now);
+    }
+
+    async fn connect(
+        address: SocketAddr,
+        origin: Option<&'static str>,
+    ) -> Result<WebSocketStream<TcpStream>, Error> {
+        let mut request = format!("ws://{address}/").into_client_request().unwrap();
+        if let Some(origin) = origin {
+            request
+                .headers_mut()
+                .insert(ORIGIN, HeaderValue::from_static(origin));
+        }
+        let stream = TcpStream::connect(address).await.unwrap();
+        tokio_tungstenite::client_async(request, stream)
+            .await
+            .map(|(websocket, _)| websocket)
+    }
+
+    async fn next_map(websocket: &mut WebSocketStream<TcpStream>) -> Value {
+        let message = timeout(Duration::from_secs(5), websocket.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        let snapshot: Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        snapshot["map"].clone()
+    }
+
+    #[tokio::test]
+    async fn http_get_answers_with_the_snapshot() {
+        let mut server = ApiServer::new();
+        set_map(&mut server, 38);
+        let address = serve_ephemeral(&server).await;
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        assert!(!head
+            .to_ascii_lowercase()
+            .contains("access-control-allow-origin"));
+        let snapshot: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(snapshot["map"], 38);
+    }
+
+    #[tokio::test]
+    async fn websocket_is_sent_the_snapshot_and_its_changes() {
+        let mut server = ApiServer::new();
+        set_map(&mut server, 38);
+        let address = serve_ephemeral(&server).await;
+
+        let mut websocket = connect(address, None).await.unwrap();
+        assert_eq!(next_map(&mut websocket).await, 38);
+        set_map(&mut server, 39);
+        assert_eq!(next_map(&mut websocket).await, 39);
+    }
+
+    #[tokio::test]
+    async fn websocket_origins_are_checked() {
+        let mut server = ApiServer::new();
+        set_map(&mut server, 38);
+        let address = serve_ephemeral(&server).await;
+
+        let mut allowed = connect(address, Some(ALLOWED_ORIGIN)).await.unwrap();
+        assert_eq!(next_map(&mut allowed).await, 38);
+        let mut null = connect(address, Some("null")).await.unwrap();
+        assert_eq!(next_map(&mut null).await, 38);
+        assert!(connect(address, Some("https://elsewhere.example"))
+            .await
+            .is_err());
+    }
+}
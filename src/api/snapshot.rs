@@ -0,0 +1,165 @@
+use {
+    crate::timer::{
+        AlertPlayer, AlertVariables, BlishColour, PhaseState, RemainingFormat, TextAlert,
+        TextAlertPriority, TimerAlertType, TimerFile,
+    },
+    serde::Serialize,
+    std::sync::Arc,
+    tokio::time::Instant,
+};
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseSnapshot {
+    pub timer: String,
+    pub timer_name: String,
+    pub phase: String,
+    pub elapsed: f32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertSnapshot {
+    pub timer: String,
+    pub phase: String,
+    pub kind: TimerAlertType,
+    pub text: String,
+    pub colour: Option<BlishColour>,
+    pub fill_colour: Option<BlishColour>,
+    /// Seconds until the alert's bar starts filling, zero once it has.
+    pub starts_in: f32,
+    /// Seconds until the alert is over.
+    pub remaining: f32,
+    pub duration: f32,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextAlertSnapshot {
+    pub timer: String,
+    pub message: String,
+    pub priority: TextAlertPriority,
+    pub remaining: f32,
+}
+
+/// Everything an overlay is told, sent whole every time so that clients keep no state.
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiSnapshot {
+    pub map: Option<u32>,
+    pub phases: Vec<PhaseSnapshot>,
+    /// Alerts of the active phases that are yet to finish, soonest first.
+    pub alerts: Vec<AlertSnapshot>,
+    pub text_alerts: Vec<TextAlertSnapshot>,
+}
+
+/**
+ * Follows the same phase and alert events the renderer gets, keeping just enough of
+ * them to describe what is currently on screen.
+ */
+#[derive(Default)]
+pub struct ApiState {
+    map_id: Option<u32>,
+    player: AlertPlayer,
+    phases: Vec<PhaseState>,
+    text_alerts: Vec<(TextAlert, Instant)>,
+}
+
+impl ApiState {
+    pub fn set_map(&mut self, map_id: u32) {
+        self.map_id = Some(map_id);
+    }
+
+    pub fn set_player(&mut self, player: AlertPlayer) {
+        self.player = player;
+    }
+
+    /// A timer only ever has one phase running, so a new one replaces the last.
+    pub fn feed(&mut self, phase_state: PhaseState) {
+        self.phases
+            .retain(|ps| !Arc::ptr_eq(&ps.timer, &phase_state.timer));
+        self.phases.push(phase_state);
+    }
+
+    pub fn reset(&mut self, timer: &Arc<TimerFile>) {
+        self.phases.retain(|ps| !Arc::ptr_eq(&ps.timer, timer));
+    }
+
+    pub fn alert_start(&mut self, alert: TextAlert, now: Instant) {
+        self.text_alerts.push((alert, now));
+    }
+
+    pub fn alert_end(&mut self, timer: &Arc<TimerFile>) {
+        self.text_alerts
+            .retain(|(alert, _)| !Arc::ptr_eq(&alert.timer, timer));
+    }
+
+    pub fn snapshot(&mut self, now: Instant) -> ApiSnapshot {
+        self.text_alerts
+            .retain(|(alert, shown)| now.saturating_duration_since(*shown) < alert.duration);
+
+        let phases = self
+            .phases
+            .iter()
+            .map(|ps| PhaseSnapshot {
+                timer: ps.timer.id.clone(),
+                timer_name: ps.timer.name(),
+                phase: ps.phase.name.clone(),
+                elapsed: now.saturating_duration_since(ps.start).as_secs_f32(),
+            })
+            .collect();
+
+        let mut alerts: Vec<AlertSnapshot> = self
+            .phases
+            .iter()
+            .flat_map(|ps| ps.alerts.iter().map(move |alert| (ps, alert)))
+            .filter(|(ps, alert)| alert.end(ps.start) > now)
+            .map(|(ps, alert)| {
+                let remaining = alert.remaining(ps.start, now);
+                let variables = AlertVariables {
+                    remaining,
+                    remaining_format: RemainingFormat::Seconds,
+                    phase: &ps.phase.name,
+                    timer: &ps.timer.name,
+                    player: &self.player,
+                };
+                AlertSnapshot {
+                    timer: ps.timer.id.clone(),
+                    phase: ps.phase.name.clone(),
+                    kind: alert.kind,
+                    text: variables.render(&alert.text),
+                    colour: alert.colour,
+                    fill_colour: alert.fill_colour,
+                    starts_in: alert
+                        .start(ps.start)
+                        .saturating_duration_since(now)
+                        .as_secs_f32(),
+                    remaining: remaining.as_secs_f32(),
+                    duration: alert.duration,
+                }
+            })
+            .collect();
+        alerts.sort_by(|a, b| a.remaining.total_cmp(&b.remaining));
+
+        let text_alerts = self
+            .text_alerts
+            .iter()
+            .map(|(alert, shown)| TextAlertSnapshot {
+                timer: alert.timer.id.clone(),
+                message: alert.message.clone(),
+                priority: alert.priority,
+                remaining: alert
+                    .duration
+                    .saturating_sub(now.saturating_duration_since(*shown))
+                    .as_secs_f32(),
+            })
+            .collect();
+
+        ApiSnapshot {
+            map: self.map_id,
+            phases,
+            alerts,
+            text_alerts,
+        }
+    }
+}
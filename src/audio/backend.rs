@@ -0,0 +1,89 @@
+use {
+    std::path::{Path, PathBuf},
+    tokio::time::Instant,
+};
+
+#[cfg(feature = "sounds")]
+use {
+    anyhow::{anyhow, Context},
+    rodio::{OutputStream, OutputStreamBuilder},
+    std::{fs::File, io::BufReader},
+};
+
+/**
+ * Anything that can actually make noise.
+ *
+ * Playback must not block: the scheduler calls this from its tick and expects
+ * the sound to keep playing in the background.
+ */
+pub trait PlaybackBackend {
+    fn play(&mut self, path: &Path, volume: f32) -> anyhow::Result<()>;
+}
+
+impl<B: PlaybackBackend + ?Sized> PlaybackBackend for Box<B> {
+    fn play(&mut self, path: &Path, volume: f32) -> anyhow::Result<()> {
+        (**self).play(path, volume)
+    }
+}
+
+/// Used when no audio device is available; sounds are silently dropped.
+#[derive(Debug, Default)]
+pub struct NullBackend;
+
+impl PlaybackBackend for NullBackend {
+    fn play(&mut self, path: &Path, volume: f32) -> anyhow::Result<()> {
+        log::debug!("Not playing {path:?} at volume {volume}, no audio backend");
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PlayedSound {
+    pub path: PathBuf,
+    pub volume: f32,
+    pub at: Instant,
+}
+
+/// Keeps a record of everything it was asked to play instead of playing it.
+#[derive(Debug, Default)]
+pub struct RecordingBackend {
+    pub played: Vec<PlayedSound>,
+}
+
+impl PlaybackBackend for RecordingBackend {
+    fn play(&mut self, path: &Path, volume: f32) -> anyhow::Result<()> {
+        self.played.push(PlayedSound {
+            path: path.to_path_buf(),
+            volume,
+            at: Instant::now(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sounds")]
+pub struct RodioBackend {
+    stream: OutputStream,
+}
+
+#[cfg(feature = "sounds")]
+impl RodioBackend {
+    pub fn new() -> anyhow::Result<Self> {
+        let mut stream = OutputStreamBuilder::open_default_stream()
+            .map_err(|e| anyhow!("Couldn't open the default audio device: {e}"))?;
+        stream.log_on_drop(false);
+        Ok(Self { stream })
+    }
+}
+
+#[cfg(feature = "sounds")]
+impl PlaybackBackend for RodioBackend {
+    fn play(&mut self, path: &Path, volume: f32) -> anyhow::Result<()> {
+        let file = File::open(path).with_context(|| format!("Couldn't open sound {path:?}"))?;
+        let sink = rodio::play(self.stream.mixer(), BufReader::new(file))
+            .map_err(|e| anyhow!("Couldn't play sound {path:?}: {e}"))?;
+        sink.set_volume(volume);
+        sink.detach();
+        Ok(())
+    }
+}
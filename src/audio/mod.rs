@@ -0,0 +1,46 @@
+pub mod backend;
+pub mod scheduler;
+
+#[allow(unused_imports)]
+pub use {
+    backend::{NullBackend, PlaybackBackend, PlayedSound, RecordingBackend},
+    scheduler::{ScheduledSound, SoundEvent, SoundScheduler},
+};
+
+#[cfg(feature = "sounds")]
+pub use backend::RodioBackend;
+
+use tokio::sync::mpsc::{Receiver, Sender};
+
+pub fn load(receiver: Receiver<SoundEvent>) {
+    #[cfg(feature = "sounds")]
+    let backend: Box<dyn PlaybackBackend> = match RodioBackend::new() {
+        Ok(backend) => Box::new(backend),
+        Err(error) => {
+            log::error!("Sounds will not play: {error:?}");
+            Box::new(NullBackend)
+        }
+    };
+    #[cfg(not(feature = "sounds"))]
+    let backend: Box<dyn PlaybackBackend> = Box::new(NullBackend);
+
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(error) => {
+            log::error!("Error! {}", error);
+            return;
+        }
+    };
+    rt.block_on(SoundScheduler::new(backend).run(receiver));
+}
+
+pub fn sender() -> Option<Sender<SoundEvent>> {
+    crate::SOUND_SENDER
+        .try_read()
+        .as_ref()
+        .ok()
+        .and_then(|s| (*s).clone())
+}
@@ -0,0 +1,252 @@
+use {
+    super::PlaybackBackend,
+    crate::timer::{PhaseState, TimerFile},
+    std::{path::PathBuf, sync::Arc},
+    tokio::{
+        sync::mpsc::Receiver,
+        time::{interval, Duration, Instant},
+    },
+};
+
+/// How late a sound may be queued and still be played, rather than dropped as skipped over.
+const LATE_GRACE: Duration = Duration::from_millis(250);
+
+pub enum SoundEvent {
+    SoundFeed(PhaseState),
+    SoundReset(Arc<TimerFile>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ScheduledSound {
+    pub timer: Arc<TimerFile>,
+    pub name: String,
+    pub path: PathBuf,
+    pub volume: f32,
+    pub at: Instant,
+}
+
+pub struct SoundScheduler<B: PlaybackBackend> {
+    backend: B,
+    pending: Vec<ScheduledSound>,
+}
+
+impl<B: PlaybackBackend> SoundScheduler<B> {
+    pub fn new(backend: B) -> Self {
+        Self {
+            backend,
+            pending: Default::default(),
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn pending(&self) -> &[ScheduledSound] {
+        &self.pending
+    }
+
+    /**
+     * Queues up the sounds of a freshly started phase.
+     * Sounds are resolved relative to the timer file that provided them,
+     * so a timer without a path on disk can't play anything.
+     * Sounds that are already well in the past, such as after a time skip, are dropped.
+     */
+    pub fn feed(&mut self, phase_state: &PhaseState, now: Instant) {
+        let Some(timer_path) = &phase_state.timer.path else {
+            return;
+        };
+        let base = timer_path.parent().map(PathBuf::from).unwrap_or_default();
+        for sound in &phase_state.sounds {
+            let at = sound.start(phase_state.start);
+            if at + LATE_GRACE < now {
+                continue;
+            }
+            self.pending.push(ScheduledSound {
+                timer: phase_state.timer.clone(),
+                name: sound.name.clone(),
+                path: sound.file.to_path(&base),
+                volume: sound.volume,
+                at,
+            });
+        }
+        self.pending.sort_by_key(|sound| sound.at);
+    }
+
+    pub fn reset(&mut self, timer: &Arc<TimerFile>) {
+        self.pending.retain(|sound| !Arc::ptr_eq(&sound.timer, timer));
+    }
+
+    pub fn handle_event(&mut self, event: SoundEvent, now: Instant) {
+        match event {
+            SoundEvent::SoundFeed(phase_state) => self.feed(&phase_state, now),
+            SoundEvent::SoundReset(timer) => self.reset(&timer),
+        }
+    }
+
+    /// Plays, and forgets about, everything that has come due by `now`.
+    pub fn tick(&mut self, now: Instant) {
+        let due = self.pending.partition_point(|sound| sound.at <= now);
+        for sound in self.pending.drain(..due) {
+            log::info!(
+                "Playing sound \"{}\" for {}: {:?} at volume {}",
+                sound.name,
+                sound.timer.name(),
+                sound.path,
+                sound.volume
+            );
+            if let Err(error) = self.backend.play(&sound.path, sound.volume) {
+                log::error!("Failed to play sound \"{}\": {error:?}", sound.name);
+            }
+        }
+    }
+
+    pub async fn run(mut self, mut receiver: Receiver<SoundEvent>) {
+        let mut sound_interval = interval(Duration::from_millis(20));
+        loop {
+            tokio::select! {
+                evt = receiver.recv() => match evt {
+                    Some(evt) => self.handle_event(evt, Instant::now()),
+                    None => break,
+                },
+                _ = sound_interval.tick() => self.tick(Instant::now()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            audio::RecordingBackend,
+            timer::{ManualClock, Position, TimerEvent, TimerMachine},
+        },
+        glam::Vec3,
+        serde_json::json,
+        tokio::sync::mpsc::channel,
+    };
+
+    fn location(x: f32, radius: f32) -> serde_json::Value {
+        json!({
+            "type": "location",
+            "position": [x, 0.0, 0.0],
+            "radius": radius,
+            "requireEntry": true,
+        })
+    }
+
+    fn timer() -> Arc<TimerFile> {
+        let timer = json!({
+            "path": "timers/test.bhtimer",
+            "id": "test",
+            "name": "Test",
+            "category": "Tests",
+            "description": "",
+            "author": "",
+            "icon": "",
+            "map": 1,
+            "reset": location(1000.0, 1.0),
+            "phases": [{
+                "name": "Start",
+                "start": location(0.0, 10.0),
+                "sounds": [
+                    { "name": "Soon", "file": "soon.wav", "volume": 0.5, "timestamps": [1.0] },
+                    { "name": "Now", "file": "now.wav", "timestamps": [3.0] },
+                ],
+            }],
+        });
+        Arc::new(serde_json::from_value(timer).unwrap())
+    }
+
+    /// Starts the timer's first phase at `clock`'s time, the way the controller would.
+    async fn phase_state(timer: Arc<TimerFile>, clock: Arc<ManualClock>) -> PhaseState {
+        let (sender, mut receiver) = channel(16);
+        let mut machine = TimerMachine::with_clock(timer, sender, clock);
+        machine.update_on_map(1);
+        machine.tick(Position::Vec3(Vec3::ZERO)).await;
+        while let Ok(event) = receiver.try_recv() {
+            if let TimerEvent::Feed(phase_state) = event {
+                return phase_state;
+            }
+        }
+        panic!("the test timer's phase should have started");
+    }
+
+    fn played(scheduler: &SoundScheduler<RecordingBackend>) -> Vec<(PathBuf, f32)> {
+        scheduler
+            .backend()
+            .played
+            .iter()
+            .map(|sound| (sound.path.clone(), sound.volume))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn sounds_play_once_each_when_due() {
+        let start = Instant::now();
+        let clock = Arc::new(ManualClock::new(start));
+        let phase_state = phase_state(timer(), clock).await;
+        let mut scheduler = SoundScheduler::new(RecordingBackend::default());
+        scheduler.handle_event(SoundEvent::SoundFeed(phase_state), start);
+        assert_eq!(scheduler.pending().len(), 2);
+
+        scheduler.tick(start + Duration::from_millis(500));
+        assert!(played(&scheduler).is_empty());
+        scheduler.tick(start + Duration::from_secs(1));
+        assert_eq!(
+            played(&scheduler),
+            [(PathBuf::from("timers/soon.wav"), 0.5)]
+        );
+        scheduler.tick(start + Duration::from_secs(2));
+        assert_eq!(played(&scheduler).len(), 1);
+        scheduler.tick(start + Duration::from_secs(5));
+        assert_eq!(
+            played(&scheduler),
+            [
+                (PathBuf::from("timers/soon.wav"), 0.5),
+                (PathBuf::from("timers/now.wav"), 1.0),
+            ]
+        );
+        assert!(scheduler.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reset_drops_the_timers_pending_sounds() {
+        let start = Instant::now();
+        let clock = Arc::new(ManualClock::new(start));
+        let timer = timer();
+        let phase_state = phase_state(timer.clone(), clock).await;
+        let mut scheduler = SoundScheduler::new(RecordingBackend::default());
+        scheduler.handle_event(SoundEvent::SoundFeed(phase_state), start);
+        scheduler.tick(start + Duration::from_secs(1));
+
+        scheduler.handle_event(
+            SoundEvent::SoundReset(timer),
+            start + Duration::from_secs(2),
+        );
+        assert!(scheduler.pending().is_empty());
+        scheduler.tick(start + Duration::from_secs(5));
+        assert_eq!(
+            played(&scheduler),
+            [(PathBuf::from("timers/soon.wav"), 0.5)]
+        );
+    }
+
+    #[tokio::test]
+    async fn sounds_already_well_past_are_skipped() {
+        let start = Instant::now();
+        let clock = Arc::new(ManualClock::new(start));
+        let phase_state = phase_state(timer(), clock).await;
+        let mut scheduler = SoundScheduler::new(RecordingBackend::default());
+        // fed late, as after a time skip: the first sound is long gone, the second is not
+        scheduler.handle_event(
+            SoundEvent::SoundFeed(phase_state),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(scheduler.pending().len(), 1);
+        scheduler.tick(start + Duration::from_secs(3));
+        assert_eq!(played(&scheduler), [(PathBuf::from("timers/now.wav"), 1.0)]);
+    }
+}
@@ -1,10 +1,12 @@
 #[cfg(feature = "markers")]
 use {
     crate::marker::{
+        agent::{AgentPreset, AgentPresetFile, AgentStep, SquadMember},
         atomic::{CurrentPerspective, MarkerInputData, MinimapPlacement, ScreenPoint},
-        format::{MarkerSet, RuntimeMarkers},
+        format::{MarkerSet, MarkerType, RuntimeMarkers},
     },
-    arcdps::extras::UserInfoOwned,
+    arcdps::extras::{user::UserRole, UserInfoOwned},
+    nexus::data_link::mumble::UiState,
     tokio::task::JoinHandle,
     windows::Win32::{
         Foundation::POINT,
@@ -14,23 +16,32 @@ use {
 };
 use {
     crate::{
+        audio::{self, SoundEvent},
+        events,
         marker::{
             atomic::ScreenVector,
             format::{MarkerEntry, MarkerFiletype},
         },
         render::TextFont,
-        settings::{MarkerAutoPlaceSettings, RemoteSource, Settings, SettingsLock, SourcesFile},
-        timer::{CombatState, Position, TimerFile, TimerMachine},
-        MumbleIdentityUpdate, RenderEvent, IMGUI_TEXTURES, SETTINGS, SOURCES,
+        settings::{
+            MarkerAutoPlaceSettings, RemoteSource, Settings, SettingsLock, SourcesFile,
+            TimerOverrideChange, TimerSettings,
+        },
+        timer::{
+            AlertPlayer, AttemptHistory, BreakbarState, CombatState, CombatTriggerEvent,
+            PhaseState, Position, RemainingFormat, TimerEvent, TimerFile, TimerMachine,
+            TimerSource, TriggerAgent,
+        },
+        trace::{MumbleSample, Trace, TraceEvent, TraceRecorder},
+        watcher::DataWatcher,
+        MumbleIdentityUpdate, RenderEvent, IMGUI_TEXTURES, REQUESTED_TEXTURES, SETTINGS, SOURCES,
     },
     anyhow::anyhow,
     arcdps::{evtc::event::Event as arcEvent, AgentOwned},
-    glam::{f32::Vec3, Vec2},
+    glam::f32::Vec3,
     nexus::{
         data_link::{
-            get_mumble_link_ptr,
-            mumble::{MumblePtr, UiState},
-            read_nexus_link, MumbleLink,
+            get_mumble_link_ptr, mumble::MumblePtr, MumbleLink,
         },
         gamebind::invoke_gamebind_async,
         paths::get_addon_dir,
@@ -39,11 +50,12 @@ use {
         texture_receive,
     },
     relative_path::RelativePathBuf,
+    serde::{Deserialize, Serialize},
     std::{
         collections::{HashMap, HashSet},
         ffi::OsStr,
         fs::exists,
-        path::PathBuf,
+        path::{Path, PathBuf},
         sync::{Arc, RwLock},
         time::SystemTime,
     },
@@ -51,11 +63,8 @@ use {
     tokio::{
         fs::create_dir_all,
         runtime, select,
-        sync::{
-            mpsc::{Receiver, Sender},
-            Mutex,
-        },
-        time::{interval, sleep, Duration},
+        sync::mpsc::{channel, Receiver, Sender},
+        time::{interval, sleep, Duration, Instant},
     },
     windows::Win32::{
         Foundation::GetLastError,
@@ -70,9 +79,15 @@ use {
 };
 
 #[cfg(feature = "space")]
-use crate::space::dx11::PerspectiveInputData;
+use crate::space::{
+    dx11::PerspectiveInputData,
+    engine::{Engine, SpaceEvent},
+};
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "api")]
+use crate::api::{self, ApiEvent};
+
+#[derive(Debug)]
 pub struct Controller {
     #[cfg(feature = "markers")]
     pub rtapi_squad: HashMap<String, GroupMemberOwned>,
@@ -83,24 +98,43 @@ pub struct Controller {
     #[cfg(feature = "markers")]
     pub markers: HashMap<String, Vec<Arc<MarkerSet>>>,
     #[cfg(feature = "markers")]
-    pub spent_markers: HashSet<Arc<MarkerSet>>,
+    /// Sets that have been placed automatically, and when, until their re-arm policy allows it again.
+    pub spent_markers: HashMap<Arc<MarkerSet>, Instant>,
     #[cfg(feature = "markers")]
     pub map_id_to_markers: HashMap<u32, HashSet<Arc<MarkerSet>>>,
     #[cfg(feature = "markers")]
     pub marker_autoplace: Option<MarkerAutoPlaceSettings>,
+    #[cfg(feature = "markers")]
+    pub agent_presets: Vec<Arc<AgentPreset>>,
+    /// The preset being applied, one member at a time, and how far through it we are.
+    #[cfg(feature = "markers")]
+    pub agent_steps: (Vec<AgentStep>, usize),
+    /// Account names of the players arcdps has told us about, by agent id.
+    #[cfg(feature = "markers")]
+    pub arc_agents: HashMap<usize, String>,
+    /// The agent arcdps last told us we targeted.
+    #[cfg(feature = "markers")]
+    pub arc_target: Option<usize>,
     pub rt_sender: Sender<RenderEvent>,
+    /// Handed to every timer machine, and forwarded on by [Controller::forward_timer_events].
+    timer_sender: Sender<TimerEvent>,
     pub cached_identity: Option<MumbleIdentityUpdate>,
     pub mumble_pointer: Option<MumblePtr>,
     pub map_id: Option<u32>,
     pub player_position: Option<Vec3>,
-    alert_sem: Arc<Mutex<()>>,
     pub timers: Vec<Arc<TimerFile>>,
     pub current_timers: Vec<TimerMachine>,
-    pub sources_to_timers: HashMap<Arc<RemoteSource>, Vec<Arc<TimerFile>>>,
+    pub sources_to_timers: HashMap<String, Vec<Arc<TimerFile>>>,
     pub map_id_to_timers: HashMap<u32, Vec<Arc<TimerFile>>>,
     settings: SettingsLock,
     last_fov: f32,
     scaling: f32,
+    trace_recorder: Option<TraceRecorder>,
+    replaying_trace: bool,
+    live_identity: Option<MumbleIdentityUpdate>,
+    alert_player: AlertPlayer,
+    /// None if the history file couldn't be read, so that it isn't overwritten.
+    attempt_history: Option<AttemptHistory>,
 }
 
 impl Controller {
@@ -122,6 +156,8 @@ impl Controller {
             let sources = Arc::new(RwLock::new(sources));
             let _ = SOURCES.set(sources);
             let settings = Settings::load_access(&addon_dir.clone()).await;
+            let (timer_sender, timer_receiver) = channel(32);
+            tokio::spawn(Self::forward_timer_events(timer_receiver, rt_sender.clone()));
             let mut state = Controller {
                 #[cfg(feature = "markers")]
                 rtapi_squad: Default::default(),
@@ -129,9 +165,18 @@ impl Controller {
                 extras_squad: Default::default(),
                 #[cfg(feature = "markers")]
                 marker_autoplace: Default::default(),
+                #[cfg(feature = "markers")]
+                agent_presets: Default::default(),
+                #[cfg(feature = "markers")]
+                agent_steps: Default::default(),
+                #[cfg(feature = "markers")]
+                arc_agents: Default::default(),
+                #[cfg(feature = "markers")]
+                arc_target: Default::default(),
                 last_fov: 0.0,
                 previous_combat_state: Default::default(),
                 rt_sender,
+                timer_sender,
                 settings,
                 #[cfg(feature = "markers")]
                 markers: Default::default(),
@@ -144,12 +189,16 @@ impl Controller {
                 mumble_pointer: mumble_link,
                 map_id: Default::default(),
                 player_position: Default::default(),
-                alert_sem: Default::default(),
                 timers: Default::default(),
                 current_timers: Default::default(),
                 sources_to_timers: Default::default(),
                 map_id_to_timers: Default::default(),
                 scaling: 0.0f32,
+                trace_recorder: Default::default(),
+                replaying_trace: false,
+                live_identity: Default::default(),
+                alert_player: Default::default(),
+                attempt_history: Default::default(),
             };
             let _ = SETTINGS.set(state.settings.clone());
             let settings = SETTINGS.get().unwrap();
@@ -162,12 +211,18 @@ impl Controller {
             state.setup_timers().await;
             #[cfg(feature = "markers")]
             state.setup_markers().await;
+            state.load_attempt_history(&addon_dir).await;
+            #[cfg(feature = "api")]
+            state.configure_api().await;
+            let mut watcher = DataWatcher::new();
+            watcher.watch(state.watched_dirs().await);
             let mut taimi_interval = interval(Duration::from_millis(125));
             let mut mumblelink_interval = interval(Duration::from_millis(20));
             loop {
                 select! {
                     evt = controller_receiver.recv() => match evt {
                         Some(evt) => {
+                            let changes_sources = evt.changes_sources();
                             match state.handle_event(evt).await {
                                 Ok(true) => (),
                                 Ok(false) => break,
@@ -175,19 +230,29 @@ impl Controller {
                                     log::error!("Error! {}", error)
                                 }
                             }
+                            if changes_sources {
+                                watcher.watch(state.watched_dirs().await);
+                            }
                         },
                         None => {
                             break
                         },
                     },
+                    _ = watcher.recv() => (),
                     _ = mumblelink_interval.tick() => {
                         let _ = state.mumblelink_tick().await;
                     },
                     _ = taimi_interval.tick() => {
+                        for path in watcher.settled() {
+                            state.reload_changed_file(path).await;
+                        }
                         let _ = state.tick().await;
                     },
                 }
             }
+            if let Err(error) = state.stop_trace_recording().await {
+                log::error!("Couldn't finish the trace: {error}");
+            }
         };
         let rt = match runtime::Builder::new_current_thread().enable_all().build() {
             Ok(rt) => rt,
@@ -283,14 +348,59 @@ impl Controller {
             .send(RenderEvent::MarkerData(markers.clone()))
             .await;
         self.markers = markers;
+        self.migrate_marker_settings().await;
         Ok(())
     }
 
+    /// Copies the settings of marker sets that have just been given a uuid over to it.
+    #[cfg(feature = "markers")]
+    async fn migrate_marker_settings(&self) {
+        let ids: Vec<(String, String)> = self
+            .markers
+            .values()
+            .flatten()
+            .map(|markers| (markers.legacy_id(), markers.id()))
+            .collect();
+        let mut settings_lock = self.settings.write().await;
+        settings_lock.migrate_marker_ids(&ids).await;
+        drop(settings_lock);
+    }
+
     async fn setup_markers(&mut self) {
         match self.load_markers_files().await {
             Ok(()) => (),
             Err(err) => log::error!("Error loading markers: {}", err),
         }
+        self.index_markers();
+        self.load_agent_presets().await;
+    }
+
+    #[cfg(feature = "markers")]
+    async fn load_agent_presets(&mut self) {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        match AgentPresetFile::load(&addon_dir.join("markers")).await {
+            Ok(presets) => {
+                log::info!("Loaded {} agent marker presets.", presets.len());
+                self.agent_presets = presets;
+            }
+            Err(err) => {
+                let _ = self
+                    .rt_sender
+                    .send(RenderEvent::OpenableError(
+                        format!("Error loading {}", AgentPresetFile::FILENAME),
+                        err,
+                    ))
+                    .await;
+            }
+        }
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::AgentPresets(self.agent_presets.clone()))
+            .await;
+    }
+
+    #[cfg(feature = "markers")]
+    fn index_markers(&mut self) {
         let mut map_id_to_markers: HashMap<u32, HashSet<Arc<MarkerSet>>> = HashMap::new();
         let marker_sets: Vec<_> = self.markers.values().flatten().collect();
         for set in marker_sets {
@@ -318,142 +428,409 @@ impl Controller {
         let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
         let adhoc_timers_dir = addon_dir.join("timers");
         if exists(&adhoc_timers_dir).expect("oh no i cant access my own addon dir") {
-            let adhoc_timers = TimerFile::load_many_sourceless(&adhoc_timers_dir, 100)
-                .await
-                .expect("wah");
-            self.timers.extend(adhoc_timers);
+            match TimerFile::load_many_sourceless(&adhoc_timers_dir, 100).await {
+                Ok(adhoc_timers) => self.timers.extend(adhoc_timers),
+                Err(err) => {
+                    let _ = self
+                        .rt_sender
+                        .send(RenderEvent::OpenableError(
+                            format!("Error loading timers from {adhoc_timers_dir:?}"),
+                            err,
+                        ))
+                        .await;
+                }
+            }
         } else {
             create_dir_all(adhoc_timers_dir)
                 .await
                 .expect("Can't create timers dir");
         }
-        for timer in &self.timers {
-            if let Some(association) = &timer.association {
-                self.sources_to_timers
-                    .entry(association.clone())
-                    .or_default();
-                if let Some(val) = self.sources_to_timers.get_mut(association) {
-                    val.push(timer.clone());
+        for timer in self.timers.clone() {
+            self.index_timer(&timer);
+        }
+        log::info!("Set up {} timers.", self.timers.len());
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::TimerData(self.timers.clone()))
+            .await;
+    }
+
+    fn index_timer(&mut self, timer: &Arc<TimerFile>) {
+        if let Some(association) = &timer.association {
+            self.sources_to_timers
+                .entry(association.to_string())
+                .or_default()
+                .push(timer.clone());
+        }
+        // Handle map to timers
+        for &map_id in timer.map_ids.as_slice() {
+            self.map_id_to_timers
+                .entry(map_id)
+                .or_default()
+                .push(timer.clone());
+        }
+        let association = match &timer.association {
+            Some(s) => format!("{}", s),
+            None => "unassociated".to_string(),
+        };
+        // Handle id to timer file allocation
+        log::info!(
+            "Set up {4} {0}: {3} for map {1}, category {2}",
+            timer.id,
+            timer.name.replace("\n", " "),
+            timer.map_ids,
+            timer.category,
+            association,
+        );
+    }
+
+    /// The directories whose files are reloaded as they change.
+    async fn watched_dirs(&self) -> HashSet<PathBuf> {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        let mut dirs = HashSet::from([addon_dir.join("timers")]);
+        #[cfg(feature = "markers")]
+        dirs.insert(addon_dir.join("markers"));
+        let settings_lock = self.settings.read().await;
+        dirs.extend(
+            settings_lock
+                .remotes
+                .iter()
+                .filter_map(|remote| remote.installed_path.clone()),
+        );
+        dirs
+    }
+
+    async fn reload_changed_file(&mut self, path: PathBuf) {
+        #[cfg(feature = "markers")]
+        if path
+            .file_name()
+            .is_some_and(|name| name == AgentPresetFile::FILENAME)
+        {
+            return self.load_agent_presets().await;
+        }
+        match path.extension().and_then(OsStr::to_str) {
+            Some("bhtimer") => self.reload_timer_file(path).await,
+            #[cfg(feature = "markers")]
+            Some("markers") => self.reload_markers_file(path).await,
+            _ => (),
+        }
+    }
+
+    /// Which source a timer file under one of the watched directories belongs to.
+    async fn timer_file_association(&self, path: &Path) -> Option<Option<Arc<dyn TimerSource>>> {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        if path.starts_with(addon_dir.join("timers")) {
+            return Some(None);
+        }
+        let settings_lock = self.settings.read().await;
+        settings_lock
+            .remotes
+            .iter()
+            .find(|remote| {
+                remote
+                    .installed_path
+                    .as_ref()
+                    .is_some_and(|installed| path.starts_with(installed))
+            })
+            .map(|remote| Some(remote.source.clone() as Arc<dyn TimerSource>))
+    }
+
+    /**
+     * Swaps out the timers loaded from a single file, including any running for this map.
+     * If the file no longer loads, the previous version is kept running.
+     */
+    async fn reload_timer_file(&mut self, path: PathBuf) {
+        let Some(association) = self.timer_file_association(&path).await else {
+            return;
+        };
+        let timer = match exists(&path) {
+            Ok(true) => match TimerFile::load(&path, association).await {
+                Ok(timer) => Some(timer),
+                Err(err) => {
+                    let _ = self
+                        .rt_sender
+                        .send(RenderEvent::OpenableError(
+                            format!("Error reloading timer file {path:?}"),
+                            err,
+                        ))
+                        .await;
+                    return;
+                }
+            },
+            _ => None,
+        };
+        log::info!("Reloading the timer file at {path:?}.");
+        Self::forget_requested_textures();
+
+        let from_path = |timer: &Arc<TimerFile>| timer.path.as_deref() == Some(path.as_path());
+        self.timers.retain(|timer| !from_path(timer));
+        for timers in self.sources_to_timers.values_mut() {
+            timers.retain(|timer| !from_path(timer));
+        }
+        for timers in self.map_id_to_timers.values_mut() {
+            timers.retain(|timer| !from_path(timer));
+        }
+        self.map_id_to_timers.retain(|_, timers| !timers.is_empty());
+        for machine in &mut self.current_timers {
+            if from_path(&machine.timer) {
+                machine.cleanup().await;
+            }
+        }
+        self.current_timers
+            .retain(|machine| !from_path(&machine.timer));
+
+        if let Some(timer) = timer {
+            self.timers.push(timer.clone());
+            self.index_timer(&timer);
+            if let Some(map_id) = self.map_id.filter(|&map_id| timer.map_ids.contains(map_id)) {
+                let settings_lock = self.settings.read().await;
+                let timer_enabled = match settings_lock.timers.get(&timer.id) {
+                    Some(setting) => !setting.disabled,
+                    None => true,
                 };
+                drop(settings_lock);
+                if timer_enabled {
+                    let mut machine = self.timer_machine(timer).await;
+                    machine.update_on_map(map_id);
+                    self.current_timers.push(machine);
+                }
             }
-            // Handle map to timers
-            self.map_id_to_timers.entry(timer.map_id).or_default();
-            if let Some(val) = self.map_id_to_timers.get_mut(&timer.map_id) {
-                val.push(timer.clone());
-            };
-            let association = match &timer.association {
-                Some(s) => format!("{}", s),
-                None => "unassociated".to_string(),
-            };
-            // Handle id to timer file allocation
-            log::info!(
-                "Set up {4} {0}: {3} for map {1}, category {2}",
-                timer.id,
-                timer.name.replace("\n", " "),
-                timer.map_id,
-                timer.category,
-                association,
-            );
         }
-        log::info!("Set up {} timers.", self.timers.len());
         let _ = self
             .rt_sender
             .send(RenderEvent::TimerData(self.timers.clone()))
             .await;
     }
 
+    /// Swaps out the marker sets loaded from a single file.
+    #[cfg(feature = "markers")]
+    async fn reload_markers_file(&mut self, path: PathBuf) {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        if !path.starts_with(addon_dir.join("markers")) {
+            return;
+        }
+        let pack = match exists(&path) {
+            Ok(true) => match RuntimeMarkers::load(&path).await {
+                Ok(pack) => Some(pack),
+                Err(err) => {
+                    let _ = self
+                        .rt_sender
+                        .send(RenderEvent::OpenableError(
+                            format!("Error reloading markers file {path:?}"),
+                            err,
+                        ))
+                        .await;
+                    return;
+                }
+            },
+            _ => None,
+        };
+        log::info!("Reloading the markers file at {path:?}.");
+
+        for sets in self.markers.values_mut() {
+            sets.retain(|set| set.path.as_deref() != Some(path.as_path()));
+        }
+        if let Some(pack) = pack {
+            for (category, sets) in RuntimeMarkers::markers(vec![pack]).await {
+                self.markers.entry(category).or_default().extend(sets);
+            }
+        }
+        self.markers.retain(|_, sets| !sets.is_empty());
+        self.migrate_marker_settings().await;
+        self.index_markers();
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::MarkerData(self.markers.clone()))
+            .await;
+        if let Some(map_id) = self.map_id {
+            let markers_for_map = self
+                .map_id_to_markers
+                .get(&map_id)
+                .map(|sets| sets.iter().cloned().collect())
+                .unwrap_or_default();
+            let _ = self
+                .rt_sender
+                .send(RenderEvent::MarkerMap(markers_for_map))
+                .await;
+        }
+    }
+
     async fn tick(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
 
     async fn mumblelink_tick(&mut self) -> anyhow::Result<()> {
+        // a replay drives the same logic from the trace instead
+        if self.replaying_trace {
+            return Ok(());
+        }
         if let Some(mumble) = self.mumble_pointer {
-            let playpos = Vec3::from_array(mumble.read_avatar().position);
-            #[cfg(feature = "space")]
-            {
-                let camera = mumble.read_camera();
-                let front = Vec3::from_array(camera.front);
-                let pos = Vec3::from_array(camera.position);
-                PerspectiveInputData::swap_camera(front, pos, playpos);
-            }
-            #[cfg(feature = "markers")]
-            {
-                if let Some(map_id) = &self.map_id {
-                    if let Some(markers_for_map) = self.map_id_to_markers.get(map_id) {
-                        let mut new_spent_markers = Vec::new();
-                        for marker in markers_for_map.difference(&self.spent_markers) {
-                            if marker.trigger(playpos) {
-                                new_spent_markers.push(marker.clone());
-                            }
+            let sample = MumbleSample::read(&mumble);
+            self.record_trace(TraceEvent::Mumble(sample)).await;
+            self.handle_sample(sample).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_sample(&mut self, sample: MumbleSample) -> anyhow::Result<()> {
+        let playpos = sample.position;
+        #[cfg(feature = "space")]
+        PerspectiveInputData::swap_camera(sample.camera_front, sample.camera_position, playpos);
+        #[cfg(feature = "markers")]
+        {
+            if let Some(map_id) = &self.map_id {
+                if let Some(markers_for_map) = self.map_id_to_markers.get(map_id) {
+                    let now = Instant::now();
+                    let in_combat = sample.in_combat();
+                    let left_combat = self.previous_combat_state && !in_combat;
+                    let spent_before = self.spent_markers.len();
+                    self.spent_markers.retain(|marker, spent_at| {
+                        let rearms =
+                            marker.rearms(playpos, now.duration_since(*spent_at), left_combat);
+                        if rearms {
+                            log::debug!("Marker autoplace re-armed for {}", marker.name);
                         }
-                        self.spent_markers.extend(new_spent_markers.clone());
-                        for spent_marker in new_spent_markers {
-                            log::debug!("Marker autoplace triggered for {}", spent_marker.name);
-                            self.handle_marker_autoplace(&spent_marker).await?;
+                        !rearms
+                    });
+                    let rearmed = self.spent_markers.len() != spent_before;
+                    let mut squad_size = None;
+                    let mut new_spent_markers = Vec::new();
+                    for marker in markers_for_map {
+                        if !self.spent_markers.contains_key(marker)
+                            && marker.trigger(playpos, in_combat, || {
+                                *squad_size.get_or_insert_with(|| self.squad_members().len().max(1))
+                            })
+                        {
+                            new_spent_markers.push(marker.clone());
                         }
                     }
-                }
-                if let Some(nexus_link) = read_nexus_link() {
-                    let scaling = nexus_link.scaling;
-                    if self.scaling != scaling {
-                        MarkerInputData::from_nexus(scaling);
-                        self.scaling = scaling;
+                    for marker in &new_spent_markers {
+                        self.spent_markers.insert(marker.clone(), now);
                     }
-                }
-                let ui_state = mumble.read_ui_state();
-                let global_player_pos = Vec2::from(mumble.read_player_position());
-                let global_map = Vec2::from(mumble.read_map_center());
-                let compass_width = mumble.read_compass_width() as f32;
-                let compass_height = mumble.read_compass_height() as f32;
-                let compass_size = Vec2::new(compass_width, compass_height);
-                let compass_rotation = mumble.read_compass_rotation();
-                let map_scale = mumble.read_map_scale();
-                let perspective = CurrentPerspective::from(ui_state.contains(UiState::IS_MAP_OPEN));
-                let minimap_placement =
-                    MinimapPlacement::from(ui_state.contains(UiState::IS_COMPASS_TOP_RIGHT));
-                let rotation_enabled =
-                    ui_state.contains(UiState::DOES_COMPASS_HAVE_ROTATION_ENABLED);
-                MarkerInputData::from_tick(
-                    playpos,
-                    global_player_pos,
-                    global_map,
-                    compass_size,
-                    compass_rotation,
-                    map_scale,
-                    perspective,
-                    minimap_placement,
-                    rotation_enabled,
-                );
-            }
-            self.player_position = Some(playpos);
-            let combat_state = mumble
-                .read_context()
-                .ui_state
-                .contains(UiState::IS_IN_COMBAT);
-            if combat_state != self.previous_combat_state {
-                if combat_state {
-                    log::info!("MumbleLink: Combat begins at {:?}!", SystemTime::now());
-                    for machine in &mut self.current_timers {
-                        machine.set_combat_state(CombatState::Entered);
+                    if rearmed || !new_spent_markers.is_empty() {
+                        self.send_spent_markers().await;
                     }
-                } else {
-                    log::info!("MumbleLink: Combat ends at {:?}!", SystemTime::now());
-                    for machine in &mut self.current_timers {
-                        machine.set_combat_state(CombatState::Exited);
+                    for spent_marker in new_spent_markers {
+                        log::debug!("Marker autoplace triggered for {}", spent_marker.name);
+                        self.handle_marker_autoplace(&spent_marker).await?;
                     }
                 }
-                self.previous_combat_state = combat_state;
             }
-            if let Some(pos) = self.player_position() {
+            if let Some(scaling) = sample.scaling {
+                if self.scaling != scaling {
+                    MarkerInputData::from_nexus(scaling);
+                    self.scaling = scaling;
+                }
+            }
+            let ui_state = sample.ui_state();
+            let perspective = CurrentPerspective::from(ui_state.contains(UiState::IS_MAP_OPEN));
+            let minimap_placement =
+                MinimapPlacement::from(ui_state.contains(UiState::IS_COMPASS_TOP_RIGHT));
+            let rotation_enabled =
+                ui_state.contains(UiState::DOES_COMPASS_HAVE_ROTATION_ENABLED);
+            MarkerInputData::from_tick(
+                playpos,
+                sample.player_map_position,
+                sample.map_center,
+                sample.compass_size,
+                sample.compass_rotation,
+                sample.map_scale,
+                perspective,
+                minimap_placement,
+                rotation_enabled,
+            );
+        }
+        self.player_position = Some(playpos);
+        let combat_state = sample.in_combat();
+        if combat_state != self.previous_combat_state {
+            if combat_state {
+                log::info!("MumbleLink: Combat begins at {:?}!", SystemTime::now());
                 for machine in &mut self.current_timers {
-                    machine.tick(pos).await
+                    machine.set_combat_state(CombatState::Entered);
                 }
+            } else {
+                log::info!("MumbleLink: Combat ends at {:?}!", SystemTime::now());
+                for machine in &mut self.current_timers {
+                    machine.set_combat_state(CombatState::Exited);
+                }
+            }
+            self.previous_combat_state = combat_state;
+        }
+        if let Some(pos) = self.player_position() {
+            for machine in &mut self.current_timers {
+                machine.tick(pos).await
             }
         }
+        self.record_attempts().await;
         Ok(())
     }
 
+    async fn load_attempt_history(&mut self, addon_dir: &Path) {
+        match AttemptHistory::load(addon_dir).await {
+            Ok(history) => {
+                let _ = self
+                    .rt_sender
+                    .send(RenderEvent::AttemptStats(history.stats()))
+                    .await;
+                self.attempt_history = Some(history);
+            }
+            Err(err) => {
+                log::error!("Attempts will not be recorded, the history couldn't be loaded: {err}")
+            }
+        }
+    }
+
+    /// Stores the attempts that have come to an end since the last tick.
+    async fn record_attempts(&mut self) {
+        let mut attempts = Vec::new();
+        for machine in &mut self.current_timers {
+            attempts.extend(machine.take_attempts());
+        }
+        // replays are for checking timers over, not for setting records
+        if attempts.is_empty() || self.replaying_trace {
+            return;
+        }
+        let Some(history) = &mut self.attempt_history else {
+            return;
+        };
+        for mut attempt in attempts {
+            log::info!(
+                "Recording a {:?} attempt at {} lasting {:.1}s.",
+                attempt.outcome,
+                attempt.timer,
+                attempt.seconds()
+            );
+            attempt.character = self.alert_player.character.clone();
+            attempt.account = self.alert_player.account.clone();
+            history.record(attempt);
+        }
+        self.save_attempt_history().await;
+    }
+
+    async fn clear_attempts(&mut self, id: &str) {
+        if let Some(history) = &mut self.attempt_history {
+            history.clear(id);
+            self.save_attempt_history().await;
+        }
+    }
+
+    async fn save_attempt_history(&self) {
+        let Some(history) = &self.attempt_history else {
+            return;
+        };
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        if let Err(err) = history.save(&addon_dir).await {
+            log::error!("Couldn't save the attempt history: {err}");
+        }
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::AttemptStats(history.stats()))
+            .await;
+    }
+
     async fn handle_mumble(&mut self, identity: MumbleIdentityUpdate) {
+        self.record_trace(TraceEvent::Identity(identity.clone()))
+            .await;
         #[cfg(feature = "space")]
         {
             if self.last_fov != identity.fov {
@@ -477,6 +854,7 @@ impl Controller {
                     .await;
                 MarkerInputData::from_mapchange(new_map_id);
                 self.spent_markers = Default::default();
+                self.send_spent_markers().await;
             }
             for timer in &mut self.current_timers {
                 timer.cleanup().await;
@@ -491,58 +869,259 @@ impl Controller {
                         Some(setting) => !setting.disabled,
                         None => true,
                     };
+                    drop(settings_lock);
                     if timer_enabled {
-                        self.current_timers.push(TimerMachine::new(
-                            timer.clone(),
-                            self.alert_sem.clone(),
-                            self.rt_sender.clone(),
-                        ));
+                        let machine = self.timer_machine(timer.clone()).await;
+                        self.current_timers.push(machine);
                     }
-                    drop(settings_lock);
                 }
                 for machine in &mut self.current_timers {
                     machine.update_on_map(new_map_id)
                 }
             }
             self.map_id = Some(new_map_id);
+            #[cfg(feature = "api")]
+            Self::send_api(ApiEvent::Map(new_map_id)).await;
         }
         self.cached_identity = Some(identity);
+        self.update_alert_player().await;
+    }
+
+    /// Lets the renderer know who is playing, for filling in alert text.
+    async fn update_alert_player(&mut self) {
+        #[cfg(feature = "markers")]
+        let squad_role = self.get_role().await.map(|role| role.to_string());
+        #[cfg(not(feature = "markers"))]
+        let squad_role = None;
+        let alert_player = AlertPlayer {
+            character: self.agent.as_ref().and_then(|agent| agent.name.clone()),
+            account: crate::ACCOUNT_NAME_CELL.get().cloned(),
+            squad_role,
+        };
+        if alert_player != self.alert_player {
+            self.alert_player = alert_player.clone();
+            #[cfg(feature = "api")]
+            Self::send_api(ApiEvent::Player(alert_player.clone())).await;
+            let _ = self
+                .rt_sender
+                .send(RenderEvent::AlertPlayer(alert_player))
+                .await;
+        }
+    }
+
+    fn trigger_agent(agent: &AgentOwned) -> TriggerAgent {
+        // NPCs have no elite spec, and carry their species ID in place of a profession
+        let is_gadget = agent.prof >> 16 == 0xffff;
+        let species_id = (agent.elite == u32::MAX && !is_gadget).then_some(agent.prof);
+        TriggerAgent {
+            species_id,
+            is_self: agent.is_self != 0,
+        }
+    }
+
+    /// Picks out the parts of an ArcDPS combat event that timer triggers care about.
+    fn combat_trigger_event(
+        src: &AgentOwned,
+        dst: Option<&AgentOwned>,
+        evt: &arcEvent,
+    ) -> Option<CombatTriggerEvent> {
+        use arcdps::{Activation, BuffRemove, StateChange};
+        let agent = Self::trigger_agent(src);
+        let event = match evt.get_statechange() {
+            StateChange::None => {
+                let skill_id = evt.skill_id;
+                if evt.get_activation() == Activation::Start {
+                    CombatTriggerEvent::SkillCast { skill_id, agent }
+                } else if evt.get_buffremove() != BuffRemove::None {
+                    let agent = Self::trigger_agent(dst?);
+                    CombatTriggerEvent::BuffRemove { skill_id, agent }
+                } else if evt.buff != 0 && evt.buff_dmg == 0 && evt.value != 0 {
+                    let agent = Self::trigger_agent(dst?);
+                    CombatTriggerEvent::BuffApply { skill_id, agent }
+                } else {
+                    return None;
+                }
+            }
+            StateChange::Spawn => CombatTriggerEvent::AgentSpawn { agent },
+            StateChange::ChangeDead => CombatTriggerEvent::AgentDeath { agent },
+            StateChange::HealthUpdate => CombatTriggerEvent::Health {
+                // health percentage, multiplied by 100
+                percent: evt.dst_agent as f32 / 100.0,
+                agent,
+            },
+            StateChange::BreakbarState => {
+                let state = match evt.value {
+                    0 => BreakbarState::Active,
+                    1 => BreakbarState::Recover,
+                    2 => BreakbarState::Immune,
+                    _ => BreakbarState::None,
+                };
+                CombatTriggerEvent::Breakbar { state, agent }
+            }
+            _ => return None,
+        };
+        Some(event)
     }
 
-    async fn handle_combat_event(&mut self, src: arcdps::AgentOwned, evt: arcEvent) {
+    async fn handle_combat_event(
+        &mut self,
+        src: arcdps::AgentOwned,
+        dst: Option<arcdps::AgentOwned>,
+        evt: arcEvent,
+    ) {
+        let trigger_event = Self::combat_trigger_event(&src, dst.as_ref(), &evt);
         let is_self = src.is_self != 0;
         if is_self {
-            match &mut self.agent {
+            let character_changed = match &mut self.agent {
                 Some(agent) if src.name != agent.name => {
                     log::info!("Character changed from {:?} to {:?}!", agent.name, src.name);
                     *agent = src;
+                    true
                 }
-                Some(_agent) => (),
+                Some(_agent) => false,
                 None => {
                     log::info!("Character selected, {:?}!", src.name);
                     self.agent = Some(src);
+                    true
                 }
             };
+            if character_changed {
+                self.update_alert_player().await;
+            }
+        }
+        // the trace being replayed has its own idea of combat
+        if self.replaying_trace {
+            return;
+        }
+        if let Some(trigger_event) = trigger_event {
+            self.combat_trigger_event_feed(trigger_event).await;
         }
         use arcdps::StateChange;
         match evt.get_statechange() {
             StateChange::None => {}
             StateChange::EnterCombat => {
                 log::info!("ArcDPS: Combat begins at {}!", evt.time);
-                for machine in &mut self.current_timers {
-                    machine.set_combat_state(CombatState::Entered);
-                }
+                self.arc_combat_state(CombatState::Entered).await;
             }
             StateChange::ExitCombat => {
                 log::info!("ArcDPS: Combat ends at {}!", evt.time);
-                for machine in &mut self.current_timers {
-                    machine.set_combat_state(CombatState::Exited);
-                }
+                self.arc_combat_state(CombatState::Exited).await;
             }
             _ => (),
         }
     }
 
+    async fn arc_combat_state(&mut self, combat_state: CombatState) {
+        self.record_trace(TraceEvent::Combat(combat_state)).await;
+        for machine in &mut self.current_timers {
+            machine.set_combat_state(combat_state);
+        }
+    }
+
+    async fn combat_trigger_event_feed(&mut self, event: CombatTriggerEvent) {
+        self.record_trace(TraceEvent::CombatEvent(event.clone()))
+            .await;
+        for machine in &mut self.current_timers {
+            machine.combat_event(event.clone());
+        }
+    }
+
+    /// A trace being replayed is never recorded over again.
+    async fn record_trace(&mut self, event: TraceEvent) {
+        if self.replaying_trace {
+            return;
+        }
+        if let Some(recorder) = &mut self.trace_recorder {
+            if let Err(error) = recorder.record(event).await {
+                log::error!("Couldn't record to {:?}: {error}", recorder.path());
+            }
+        }
+    }
+
+    fn traces_dir() -> PathBuf {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        addon_dir.join("traces")
+    }
+
+    /// Finishes off the gzip stream, without which the trace can't be read back.
+    async fn stop_trace_recording(&mut self) -> anyhow::Result<()> {
+        if let Some(recorder) = self.trace_recorder.take() {
+            let path = recorder.finish().await?;
+            log::info!("Finished recording trace to {:?}", path);
+        }
+        Ok(())
+    }
+
+    async fn toggle_trace_recording(&mut self) -> anyhow::Result<()> {
+        match self.trace_recorder {
+            Some(_) => self.stop_trace_recording().await?,
+            None => {
+                let traces_dir = Self::traces_dir();
+                create_dir_all(&traces_dir).await?;
+                let recorder = TraceRecorder::create(TraceRecorder::new_path(&traces_dir)).await?;
+                log::info!("Recording trace to {:?}", recorder.path());
+                self.trace_recorder = Some(recorder);
+                // a trace is useless without knowing where it starts
+                if let Some(identity) = self.cached_identity.clone() {
+                    self.record_trace(TraceEvent::Identity(identity)).await;
+                }
+            }
+        }
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::TraceRecording(self.trace_recorder.is_some()))
+            .await;
+        Ok(())
+    }
+
+    async fn replay_trace(&mut self, path: Option<PathBuf>) -> anyhow::Result<()> {
+        if self.replaying_trace {
+            return Err(anyhow!("A trace is already being replayed"));
+        }
+        let path = match path {
+            Some(path) => path,
+            None => Trace::latest(&Self::traces_dir())
+                .await?
+                .ok_or_else(|| anyhow!("There are no traces to replay"))?,
+        };
+        let entries = Trace::load(&path).await?;
+        let sender = Self::sender().ok_or_else(|| anyhow!("Controller is not running"))?;
+        log::info!("Replaying {} entries from {:?}", entries.len(), path);
+        // start from a clean slate, as though the game had just been opened
+        self.live_identity = self.cached_identity.clone();
+        self.map_id = None;
+        self.previous_combat_state = false;
+        self.replaying_trace = true;
+        tokio::spawn(Trace::replay(entries, sender));
+        let _ = self.rt_sender.send(RenderEvent::TraceReplaying(true)).await;
+        Ok(())
+    }
+
+    async fn handle_trace_event(&mut self, event: TraceEvent) -> anyhow::Result<()> {
+        if !self.replaying_trace {
+            return Ok(());
+        }
+        match event {
+            TraceEvent::Mumble(sample) => self.handle_sample(sample).await?,
+            TraceEvent::Identity(identity) => self.handle_mumble(identity).await,
+            TraceEvent::Combat(combat_state) => self.arc_combat_state(combat_state).await,
+            TraceEvent::CombatEvent(event) => self.combat_trigger_event_feed(event).await,
+            TraceEvent::Key { index, released } => self.timer_key_index(index, released).await,
+        }
+        Ok(())
+    }
+
+    async fn trace_replay_finished(&mut self) {
+        log::info!("Trace replay finished");
+        self.replaying_trace = false;
+        self.map_id = None;
+        self.previous_combat_state = false;
+        if let Some(identity) = self.live_identity.take() {
+            self.handle_mumble(identity).await;
+        }
+        let _ = self.rt_sender.send(RenderEvent::TraceReplaying(false)).await;
+    }
+
     async fn toggle_marker(&mut self, id: &str) {
         let mut settings_lock = self.settings.write().await;
         let disabled = settings_lock.toggle_marker(id.to_string()).await;
@@ -575,11 +1154,8 @@ impl Controller {
                                 "Creating timer machine for {} as it has been enabled.",
                                 timer.id
                             );
-                            self.current_timers.push(TimerMachine::new(
-                                timer.clone(),
-                                self.alert_sem.clone(),
-                                self.rt_sender.clone(),
-                            ));
+                            let machine = self.timer_machine(timer.clone()).await;
+                            self.current_timers.push(machine);
                         }
                     }
                 }
@@ -606,11 +1182,8 @@ impl Controller {
                 let timers = timers_for_map.iter().filter(|t| t.id == id);
                 for timer in timers {
                     log::debug!("Creating timer machine for {}", timer.id);
-                    self.current_timers.push(TimerMachine::new(
-                        timer.clone(),
-                        self.alert_sem.clone(),
-                        self.rt_sender.clone(),
-                    ));
+                    let machine = self.timer_machine(timer.clone()).await;
+                    self.current_timers.push(machine);
                 }
             }
         }
@@ -628,6 +1201,36 @@ impl Controller {
         self.current_timers.retain(|t| t.timer.id != id);
     }
 
+    /// Overrides are picked up as each phase starts, so running phases are left alone.
+    async fn change_timer_override(&mut self, id: String, change: TimerOverrideChange) {
+        let mut settings_lock = self.settings.write().await;
+        settings_lock.change_timer_override(id.clone(), change).await;
+        let overrides = settings_lock
+            .timers
+            .get(&id)
+            .map(TimerSettings::overrides)
+            .unwrap_or_default();
+        drop(settings_lock);
+        for machine in self.current_timers.iter_mut().filter(|t| t.timer.id == id) {
+            machine.set_overrides(overrides.clone());
+        }
+    }
+
+    /// A machine for the timer, carrying the user's overrides for it.
+    async fn timer_machine(&self, timer: Arc<TimerFile>) -> TimerMachine {
+        let overrides = self
+            .settings
+            .read()
+            .await
+            .timers
+            .get(&timer.id)
+            .map(TimerSettings::overrides)
+            .unwrap_or_default();
+        let mut machine = TimerMachine::new(timer, self.timer_sender.clone());
+        machine.set_overrides(overrides);
+        machine
+    }
+
     async fn check_updates(&mut self) {
         let _ = self
             .rt_sender
@@ -650,11 +1253,12 @@ impl Controller {
     }
 
     async fn reload_timers(&mut self) {
+        Self::forget_requested_textures();
         self.timers.clear();
         self.sources_to_timers.clear();
         self.map_id_to_timers.clear();
         self.setup_timers().await;
-        self.reset_timers().await;
+        self.reset_timers(None).await;
     }
 
     #[cfg(feature = "markers")]
@@ -662,13 +1266,8 @@ impl Controller {
         self.load_markers_files()
             .await
             .expect("markers load failed");
-        let mut map_id_to_markers: HashMap<u32, HashSet<Arc<MarkerSet>>> = HashMap::new();
-        let marker_sets: Vec<_> = self.markers.values().flatten().collect();
-        for set in marker_sets {
-            let entry = map_id_to_markers.entry(set.map_id).or_default();
-            entry.insert(set.clone());
-        }
-        self.map_id_to_markers = map_id_to_markers;
+        self.index_markers();
+        self.load_agent_presets().await;
     }
     #[cfg(feature = "markers")]
     async fn clear_markers(&self) {
@@ -677,6 +1276,152 @@ impl Controller {
         invoke_gamebind_async(MarkerType::ClearMarkers.to_place_world_gamebind(), 10i32);
     }
 
+    #[cfg(feature = "markers")]
+    async fn clear_agent_markers(&self) {
+        invoke_gamebind_async(MarkerType::ClearMarkers.to_set_agent_gamebind(), 10i32);
+    }
+
+    /// Everyone in the squad, preferring the RTAPI as it also knows character names.
+    #[cfg(feature = "markers")]
+    fn squad_members(&self) -> Vec<SquadMember> {
+        if !self.rtapi_squad.is_empty() {
+            return self
+                .rtapi_squad
+                .values()
+                .map(|member| SquadMember {
+                    account_name: member.account_name.clone(),
+                    character_name: Some(member.character_name.clone()),
+                    subgroup: u8::try_from(member.subgroup).ok().filter(|&s| s != 0),
+                    role: match (member.is_commander, member.is_lieutenant) {
+                        (true, _) => SquadRoleState::Commander,
+                        (_, true) => SquadRoleState::Lieutenant,
+                        _ => SquadRoleState::Member,
+                    },
+                })
+                .collect();
+        }
+        self.extras_squad
+            .values()
+            .filter_map(|member| {
+                let role = match member.role {
+                    UserRole::SquadLeader => SquadRoleState::Commander,
+                    UserRole::Lieutenant => SquadRoleState::Lieutenant,
+                    UserRole::Member => SquadRoleState::Member,
+                    // invited, applied or already gone
+                    _ => return None,
+                };
+                Some(SquadMember {
+                    account_name: member.account_name.clone()?,
+                    character_name: None,
+                    // extras counts subgroups from 0
+                    subgroup: member.subgroup.checked_add(1),
+                    role,
+                })
+            })
+            .collect()
+    }
+
+    /**
+     * Resolves a preset against the squad as it is now. We can't target anyone ourselves,
+     * so the markers are then handed out one step at a time as the user targets each member.
+     */
+    #[cfg(feature = "markers")]
+    async fn start_agent_preset(&mut self, preset: Arc<AgentPreset>) {
+        let squad = self.squad_members();
+        if squad.is_empty() {
+            log::warn!(
+                "Can't apply agent preset {:?} without knowing the squad",
+                preset.name
+            );
+        }
+        let steps = preset.resolve(&squad);
+        let first = steps
+            .iter()
+            .position(|step| step.member.is_some())
+            .unwrap_or(steps.len());
+        self.agent_steps = (steps, first);
+        self.send_agent_steps().await;
+    }
+
+    /**
+     * Gives the current target the marker of the current step if `apply`, then moves on.
+     *
+     * When arcdps knows whose account the target is, the marker is only given if it is the
+     * step's member, and the step stays put otherwise. Without arcdps the target is trusted.
+     */
+    #[cfg(feature = "markers")]
+    async fn agent_preset_step(&mut self, apply: bool) {
+        let target_account = self.target_account().cloned();
+        let (steps, current) = &mut self.agent_steps;
+        let Some(step) = steps.get(*current) else {
+            return;
+        };
+        if apply {
+            if let (Some(member), Some(target)) = (&step.member, &target_account) {
+                if !member.account_name.eq_ignore_ascii_case(target) {
+                    log::warn!(
+                        "Not giving {target} the agent marker meant for {}",
+                        member.account_name
+                    );
+                    return;
+                }
+            }
+            invoke_gamebind_async(step.marker.to_set_agent_gamebind(), 10i32);
+        }
+        *current = steps
+            .iter()
+            .enumerate()
+            .skip(*current + 1)
+            .find(|(_, step)| step.member.is_some())
+            .map_or(steps.len(), |(i, _)| i);
+        self.send_agent_steps().await;
+    }
+
+    /// The account of the current target, if arcdps has told us both.
+    #[cfg(feature = "markers")]
+    fn target_account(&self) -> Option<&String> {
+        self.arc_target.and_then(|id| self.arc_agents.get(&id))
+    }
+
+    /**
+     * Arcdps reports agents coming and going, and target changes, as combat callbacks
+     * without an event. An elite of 0 is an agent being added, with its account in
+     * `dst`, or removed if it has no profession; anything else is a new target.
+     */
+    #[cfg(feature = "markers")]
+    async fn agent_tracking(&mut self, src: AgentOwned, dst: Option<AgentOwned>) {
+        let previous = self.target_account().cloned();
+        if src.elite != 0 {
+            self.arc_target = Some(src.id).filter(|&id| id != 0);
+        } else if src.prof != 0 {
+            if let Some(account) = dst.and_then(|dst| dst.name) {
+                let account = account.trim_start_matches(':').to_string();
+                self.arc_agents.insert(src.id, account);
+            }
+        } else {
+            self.arc_agents.remove(&src.id);
+        }
+        let target = self.target_account().cloned();
+        if target != previous {
+            let _ = self.rt_sender.send(RenderEvent::AgentTarget(target)).await;
+        }
+    }
+
+    #[cfg(feature = "markers")]
+    async fn stop_agent_preset(&mut self) {
+        self.agent_steps = Default::default();
+        self.send_agent_steps().await;
+    }
+
+    #[cfg(feature = "markers")]
+    async fn send_agent_steps(&self) {
+        let (steps, current) = &self.agent_steps;
+        let _ = self
+            .rt_sender
+            .send(RenderEvent::AgentSteps(steps.clone(), *current))
+            .await;
+    }
+
     #[cfg(feature = "markers")]
     fn get_viewport_point(rel: Vec2) -> POINT {
         let hwnd = unsafe { GetForegroundWindow() };
@@ -819,6 +1564,29 @@ impl Controller {
         ))
     }
 
+    #[cfg(feature = "markers")]
+    fn set_marker_id(&self, id: &str) {
+        let markers = self
+            .markers
+            .values()
+            .flatten()
+            .find(|markers| markers.id() == id)
+            .or_else(|| {
+                // other addons may still know the set by its old id
+                self.markers
+                    .values()
+                    .flatten()
+                    .find(|markers| markers.legacy_id() == id)
+            })
+            .cloned();
+        match markers {
+            Some(markers) => {
+                self.set_marker(&markers);
+            }
+            None => log::warn!("Asked to place marker set {id:?}, which does not exist"),
+        }
+    }
+
     #[cfg(feature = "markers")]
     async fn set_marker_task(
         markers: MarkerSet,
@@ -956,6 +1724,7 @@ impl Controller {
         sleep(wait_duration).await;
         let original_position = Vec2::new(original_position.x as f32, original_position.y as f32);
         Self::move_cursor_pos(original_position)?;
+        events::markers_placed(&markers);
         Ok(())
     }
 
@@ -978,6 +1747,12 @@ impl Controller {
         drop(settings_lock);
     }
 
+    async fn set_max_text_alerts(&mut self, max_text_alerts: u32) {
+        let mut settings_lock = self.settings.write().await;
+        settings_lock.set_max_text_alerts(max_text_alerts).await;
+        drop(settings_lock);
+    }
+
     async fn set_window_state(&mut self, window: String, state: Option<bool>) {
         let mut settings_lock = self.settings.write().await;
         settings_lock.set_window_state(&window, state).await;
@@ -995,6 +1770,119 @@ impl Controller {
             }
         }
     }
+    /// Hands what the timer machines report to the renderer, sound, markers, api and Nexus.
+    async fn forward_timer_events(
+        mut receiver: Receiver<TimerEvent>,
+        rt_sender: Sender<RenderEvent>,
+    ) {
+        while let Some(event) = receiver.recv().await {
+            match event {
+                TimerEvent::Feed(phase_state) => Self::forward_feed(phase_state, &rt_sender).await,
+                TimerEvent::Reset(timer) => Self::forward_reset(timer, &rt_sender).await,
+                TimerEvent::Skip { phase_state, .. } => {
+                    // Everything downstream redraws the phase from its new start.
+                    Self::forward_reset(phase_state.timer.clone(), &rt_sender).await;
+                    Self::forward_feed(phase_state, &rt_sender).await;
+                }
+                TimerEvent::AlertStart(alert) => {
+                    events::alert_fired(&alert);
+                    #[cfg(feature = "api")]
+                    Self::send_api(ApiEvent::AlertStart(alert.clone())).await;
+                    let _ = rt_sender.send(RenderEvent::AlertStart(alert)).await;
+                }
+                TimerEvent::AlertEnd(timer) => {
+                    #[cfg(feature = "api")]
+                    Self::send_api(ApiEvent::AlertEnd(timer.clone())).await;
+                    let _ = rt_sender.send(RenderEvent::AlertEnd(timer)).await;
+                }
+                TimerEvent::AlertQueue(timer, queue) => {
+                    let _ = rt_sender.send(RenderEvent::AlertQueue(timer, queue)).await;
+                }
+                TimerEvent::PhaseChanged {
+                    timer,
+                    phase,
+                    index,
+                    change,
+                } => {
+                    events::phase_changed(&timer, &phase, index, change.into());
+                }
+            }
+        }
+    }
+
+    async fn forward_feed(phase_state: PhaseState, rt_sender: &Sender<RenderEvent>) {
+        #[cfg(feature = "space")]
+        Self::send_space(SpaceEvent::MarkerFeed(phase_state.clone())).await;
+        if let Some(sound_sender) = audio::sender() {
+            let _ = sound_sender
+                .send(SoundEvent::SoundFeed(phase_state.clone()))
+                .await;
+        }
+        #[cfg(feature = "api")]
+        Self::send_api(ApiEvent::PhaseFeed(phase_state.clone())).await;
+        let _ = rt_sender.send(RenderEvent::AlertFeed(phase_state)).await;
+    }
+
+    async fn forward_reset(timer: Arc<TimerFile>, rt_sender: &Sender<RenderEvent>) {
+        #[cfg(feature = "space")]
+        Self::send_space(SpaceEvent::MarkerReset(timer.clone())).await;
+        if let Some(sound_sender) = audio::sender() {
+            let _ = sound_sender
+                .send(SoundEvent::SoundReset(timer.clone()))
+                .await;
+        }
+        #[cfg(feature = "api")]
+        Self::send_api(ApiEvent::PhaseReset(timer.clone())).await;
+        let _ = rt_sender.send(RenderEvent::AlertReset(timer)).await;
+    }
+
+    #[cfg(feature = "space")]
+    async fn send_space(event: SpaceEvent) {
+        match SETTINGS.get().and_then(|settings| settings.try_read().ok()) {
+            Some(settings) if settings.enable_katrender => (),
+            _ => return,
+        }
+        if let Some(space_sender) = Engine::sender() {
+            let _ = space_sender.send(event).await;
+        }
+    }
+
+    #[cfg(feature = "api")]
+    async fn send_api(event: ApiEvent) {
+        if let Some(api_sender) = api::sender() {
+            let _ = api_sender.send(event).await;
+        }
+    }
+
+    /// Starts, moves or stops the state API's listener to match the settings.
+    #[cfg(feature = "api")]
+    async fn configure_api(&self) {
+        let settings_lock = self.settings.read().await;
+        let event = ApiEvent::Configure {
+            enabled: settings_lock.enable_api,
+            port: settings_lock.api_port,
+            allowed_origins: settings_lock.api_allowed_origins.clone(),
+        };
+        drop(settings_lock);
+        Self::send_api(event).await;
+    }
+
+    #[cfg(feature = "api")]
+    async fn set_api_settings(&mut self, enabled: bool, port: u16) {
+        let mut settings_lock = self.settings.write().await;
+        settings_lock.set_api(enabled, port).await;
+        drop(settings_lock);
+        self.configure_api().await;
+    }
+
+    #[cfg(feature = "api")]
+    async fn set_api_allowed_origins(&mut self, allowed_origins: Vec<String>) {
+        let mut settings_lock = self.settings.write().await;
+        settings_lock.set_api_allowed_origins(allowed_origins).await;
+        drop(settings_lock);
+        self.configure_api().await;
+    }
+
     async fn toggle_katrender(&mut self) {
         let mut settings_lock = self.settings.write().await;
         settings_lock.toggle_katrender().await;
@@ -1010,33 +1898,70 @@ impl Controller {
 
     async fn timer_key_trigger(&mut self, id: String, is_release: bool) {
         let idx = id.chars().last().unwrap().to_digit(10).unwrap();
+        self.timer_key_index(idx, is_release).await;
+    }
+
+    async fn timer_key_index(&mut self, idx: u32, is_release: bool) {
+        self.record_trace(TraceEvent::Key {
+            index: idx,
+            released: is_release,
+        })
+        .await;
         for timer in &mut self.current_timers {
             timer.key_event(idx, is_release);
         }
     }
 
+    /// Lets textures that never loaded be asked for again, such as icons from a pack installed since.
+    fn forget_requested_textures() {
+        if let Ok(mut requested) = REQUESTED_TEXTURES.lock() {
+            requested.clear();
+        }
+    }
+
+    /// Lets a texture that failed to load be asked for again.
+    fn forget_requested_texture(id: &str) {
+        if let Ok(mut requested) = REQUESTED_TEXTURES.lock() {
+            requested.remove(id);
+        }
+    }
+
     async fn load_texture(&self, rel: RelativePathBuf, base: PathBuf) {
         if let Some(base) = base.parent() {
             let abs = rel.to_path(base);
+            let id = abs.to_string_lossy().to_string();
+            if !abs.exists() {
+                // asked for again whenever it's drawn, until the file turns up
+                log::debug!("Texture {rel} doesn't exist at {abs:?}");
+                Self::forget_requested_texture(&id);
+                return;
+            }
             let cally: RawTextureReceiveCallback = texture_receive!(|id, texture| {
+                let Some(texture) = texture else {
+                    log::warn!("Texture {id} failed to load.");
+                    Controller::forget_requested_texture(id);
+                    return;
+                };
                 let gooey = IMGUI_TEXTURES.get().unwrap();
                 let mut gooey_lock = gooey.write().unwrap();
-                if let Some(texture) = texture {
-                    gooey_lock
-                        .entry(id.into())
-                        .or_insert(Arc::new(texture.clone()));
-                }
+                gooey_lock
+                    .entry(id.into())
+                    .or_insert(Arc::new(texture.clone()));
                 drop(gooey_lock);
                 log::info!("Texture {id} loaded.");
             });
-            load_texture_from_file(rel.as_str(), abs, Some(cally));
+            load_texture_from_file(id.as_str(), abs, Some(cally));
         }
     }
 
-    async fn reset_timers(&mut self) {
+    /// Resets the running timer with the given ID, or all of them.
+    async fn reset_timers(&mut self, id: Option<&str>) {
         for timer in &mut self.current_timers {
-            timer.do_reset().await;
+            if id.is_none_or(|id| timer.timer.id == id) {
+                timer.do_reset().await;
+            }
         }
+        self.record_attempts().await;
     }
 
     #[cfg(feature = "markers-edit")]
@@ -1068,6 +1993,17 @@ impl Controller {
         Ok(())
     }
 
+    #[cfg(feature = "markers-edit")]
+    async fn convert_marker_file(
+        &mut self,
+        path: &PathBuf,
+        filetype: MarkerFiletype,
+    ) -> anyhow::Result<()> {
+        RuntimeMarkers::convert_file(path, filetype).await?;
+        self.reload_markers().await;
+        Ok(())
+    }
+
     #[cfg(feature = "markers-edit")]
     async fn get_marker_paths(&self) -> anyhow::Result<()> {
         let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
@@ -1084,6 +2020,24 @@ impl Controller {
         Ok(())
     }
 
+    #[cfg(feature = "timers-edit")]
+    async fn save_timer(&mut self, timer: TimerFile, filename: PathBuf) -> anyhow::Result<()> {
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        // the editor checks this too, but nothing should be written outside the ad-hoc folder
+        if filename.file_name() != Some(filename.as_os_str()) {
+            return Err(anyhow!("{filename:?} is not a plain file name"));
+        }
+        let timers_dir = addon_dir.join("timers");
+        create_dir_all(&timers_dir).await?;
+        let path = timers_dir.join(filename);
+        let contents = serde_json::to_string_pretty(&timer)?;
+        tokio::fs::write(&path, contents).await?;
+        log::info!("Saved timer {} to {path:?}", timer.id);
+        // only the saved timer is replaced, so anything running mid-encounter carries on
+        self.reload_timer_file(path).await;
+        Ok(())
+    }
+
     #[cfg(feature = "markers")]
     async fn set_marker_autoplace_settings(
         &mut self,
@@ -1115,7 +2069,7 @@ impl Controller {
             }
         }
         if !self.extras_squad.is_empty() {
-            use {crate::ACCOUNT_NAME_CELL, arcdps::extras::user::UserRole};
+            use crate::ACCOUNT_NAME_CELL;
             if let Some(account_name) = ACCOUNT_NAME_CELL.get() {
                 if let Some(squad_state) = self.extras_squad.get(account_name) {
                     return match squad_state.role {
@@ -1159,6 +2113,7 @@ impl Controller {
                 }
             }
         }
+        self.update_alert_player().await;
     }
 
     #[cfg(feature = "markers")]
@@ -1169,11 +2124,26 @@ impl Controller {
                 self.extras_squad.insert(account_name.clone(), datum);
             }
         }
+        self.update_alert_player().await;
     }
 
     #[cfg(feature = "markers")]
     async fn clear_spent_autoplace(&mut self) {
         self.spent_markers.clear();
+        self.send_spent_markers().await;
+    }
+
+    #[cfg(feature = "markers")]
+    async fn rearm_marker(&mut self, markers: &Arc<MarkerSet>) {
+        if self.spent_markers.remove(markers).is_some() {
+            self.send_spent_markers().await;
+        }
+    }
+
+    #[cfg(feature = "markers")]
+    async fn send_spent_markers(&self) {
+        let spent = self.spent_markers.keys().cloned().collect();
+        let _ = self.rt_sender.send(RenderEvent::SpentMarkers(spent)).await;
     }
 
     async fn load_texture_integrated(&mut self, identifier: String, data: Vec<u8>) {
@@ -1198,6 +2168,8 @@ impl Controller {
             #[cfg(feature = "markers")]
             ClearSpentAutoplace => self.clear_spent_autoplace().await,
             #[cfg(feature = "markers")]
+            RearmMarker(markers) => self.rearm_marker(&markers).await,
+            #[cfg(feature = "markers")]
             MarkerEnable(id) => self.enable_marker(&id).await,
             #[cfg(feature = "markers")]
             MarkerDisable(id) => self.disable_marker(&id).await,
@@ -1209,6 +2181,16 @@ impl Controller {
             RTAPISquadUpdate(change, member) => self.rtapi_squad_update(change, member).await,
             #[cfg(feature = "markers")]
             ClearMarkers => self.clear_markers().await,
+            #[cfg(feature = "markers")]
+            ClearAgentMarkers => self.clear_agent_markers().await,
+            #[cfg(feature = "markers")]
+            AgentPresetStart(preset) => self.start_agent_preset(preset).await,
+            #[cfg(feature = "markers")]
+            AgentPresetStep(apply) => self.agent_preset_step(apply).await,
+            #[cfg(feature = "markers")]
+            AgentPresetStop => self.stop_agent_preset().await,
+            #[cfg(feature = "markers")]
+            AgentTracking { src, dst } => self.agent_tracking(src, dst).await,
             ReloadData => self.reload_data().await,
             ReloadTimers => self.reload_timers().await,
             #[cfg(feature = "markers")]
@@ -1216,22 +2198,41 @@ impl Controller {
             #[cfg(feature = "markers")]
             ReloadMarkers => self.reload_markers().await,
             ToggleKatRender => self.toggle_katrender().await,
+            #[cfg(feature = "api")]
+            ApiSettings { enabled, port } => self.set_api_settings(enabled, port).await,
+            #[cfg(feature = "api")]
+            ApiAllowedOrigins(allowed_origins) => {
+                self.set_api_allowed_origins(allowed_origins).await
+            }
             OpenOpenable(key, uri) => self.open_openable(key, uri).await,
             UninstallAddon(dd) => self.uninstall_addon(&dd).await?,
+            MumbleIdentityUpdated(identity) if self.replaying_trace => {
+                self.live_identity = Some(identity)
+            }
             MumbleIdentityUpdated(identity) => self.handle_mumble(identity).await,
-            CombatEvent { src, evt } => self.handle_combat_event(src, evt).await,
+            CombatEvent { src, dst, evt } => self.handle_combat_event(src, dst, evt).await,
             TimerEnable(id) => self.enable_timer(&id).await,
             TimerDisable(id) => self.disable_timer(&id).await,
             TimerToggle(id) => self.toggle_timer(&id).await,
-            TimerReset => self.reset_timers().await,
+            TimerOverride(id, change) => self.change_timer_override(id, change).await,
+            TimerReset(id) => self.reset_timers(id.as_deref()).await,
+            ClearAttempts(id) => self.clear_attempts(&id).await,
             CheckDataSourceUpdates => self.check_updates().await,
             #[cfg(feature = "markers")]
             SetMarker(t) => {
                 self.set_marker(&t);
             }
+            #[cfg(feature = "markers")]
+            SetMarkerId(id) => self.set_marker_id(&id),
             TimerKeyTrigger(id, is_release) => self.timer_key_trigger(id, is_release).await,
+            TimerKeyIndex(idx, is_release) => self.timer_key_index(idx, is_release).await,
+            TraceRecordToggle => self.toggle_trace_recording().await?,
+            TraceReplay(path) => self.replay_trace(path).await?,
+            TraceReplayEvent(event) => self.handle_trace_event(event).await?,
+            TraceReplayFinished => self.trace_replay_finished().await,
             DoDataSourceUpdate { source } => self.do_update(&source).await,
             ProgressBarStyle(style) => self.progress_bar_style(style).await,
+            MaxTextAlerts(max_text_alerts) => self.set_max_text_alerts(max_text_alerts).await,
             WindowState(window, state) => self.set_window_state(window, state).await,
             LoadTexture(rel, base) => self.load_texture(rel, base).await,
             LoadTextureIntegrated(identifier, data) => {
@@ -1247,7 +2248,11 @@ impl Controller {
             } => self.delete_marker(&path, category, idx).await?,
             #[cfg(feature = "markers-edit")]
             GetMarkerPaths => self.get_marker_paths().await?,
-            Quit => return Ok(false),
+            #[cfg(feature = "markers-edit")]
+            ConvertMarkerFile(path, filetype) => self.convert_marker_file(&path, filetype).await?,
+            #[cfg(feature = "timers-edit")]
+            SaveTimer(timer, filename) => self.save_timer(timer, filename).await?,
+            Quit => return self.stop_trace_recording().await.map(|()| false),
             // I forget why we needed this, but I think it's a holdover from the buttplug one o:
             //_ => (),
         }
@@ -1269,7 +2274,8 @@ impl Controller {
     }
 }
 
-#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Display)]
+#[derive(Ord, PartialOrd, Eq, PartialEq, Debug, Clone, Display, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub enum SquadRoleState {
     Member,
     Lieutenant,
@@ -1283,6 +2289,7 @@ pub enum ProgressBarStyleChange {
     Shadow(bool),
     Height(f32),
     Font(TextFont),
+    RemainingFormat(RemainingFormat),
 }
 
 #[derive(Debug, Clone, Display)]
@@ -1304,6 +2311,8 @@ pub enum ControllerEvent {
     #[cfg(feature = "markers")]
     ClearSpentAutoplace,
     #[cfg(feature = "markers")]
+    RearmMarker(Arc<MarkerSet>),
+    #[cfg(feature = "markers")]
     ExtrasSquadUpdate(Vec<UserInfoOwned>),
     #[cfg(feature = "markers")]
     RTAPISquadUpdate(SquadState, GroupMemberOwned),
@@ -1311,9 +2320,25 @@ pub enum ControllerEvent {
     #[cfg(feature = "markers")]
     ClearMarkers,
     #[cfg(feature = "markers")]
+    ClearAgentMarkers,
+    #[cfg(feature = "markers")]
+    AgentPresetStart(Arc<AgentPreset>),
+    #[cfg(feature = "markers")]
+    #[strum(to_string = "Agent preset step, applying {0}")]
+    AgentPresetStep(bool),
+    #[cfg(feature = "markers")]
+    AgentPresetStop,
+    #[cfg(feature = "markers")]
+    AgentTracking {
+        src: AgentOwned,
+        dst: Option<AgentOwned>,
+    },
+    #[cfg(feature = "markers")]
     MarkerAutoPlaceSettings(MarkerAutoPlaceSettings),
     #[cfg(feature = "markers")]
     SetMarker(Arc<MarkerSet>),
+    #[cfg(feature = "markers")]
+    SetMarkerId(String),
     #[cfg(feature = "markers-edit")]
     SaveMarker(MarkerSaveEvent),
     #[cfg(feature = "markers-edit")]
@@ -1324,20 +2349,39 @@ pub enum ControllerEvent {
     },
     #[cfg(feature = "markers-edit")]
     GetMarkerPaths,
+    #[cfg(feature = "markers-edit")]
+    ConvertMarkerFile(PathBuf, MarkerFiletype),
+    #[cfg(feature = "timers-edit")]
+    SaveTimer(TimerFile, PathBuf),
     UninstallAddon(Arc<RemoteSource>),
     MumbleIdentityUpdated(MumbleIdentityUpdate),
     ToggleKatRender,
+    #[cfg(feature = "api")]
+    ApiSettings {
+        enabled: bool,
+        port: u16,
+    },
+    #[cfg(feature = "api")]
+    ApiAllowedOrigins(Vec<String>),
     CombatEvent {
         src: arcdps::AgentOwned,
+        dst: Option<arcdps::AgentOwned>,
         evt: arcEvent,
     },
     DoDataSourceUpdate {
         source: Arc<RemoteSource>,
     },
     ProgressBarStyle(ProgressBarStyleChange),
+    MaxTextAlerts(u32),
     WindowState(String, Option<bool>),
     #[strum(to_string = "Id {0}, pressed {1}")]
     TimerKeyTrigger(String, bool),
+    #[strum(to_string = "Index {0}, released {1}")]
+    TimerKeyIndex(u32, bool),
+    TraceRecordToggle,
+    TraceReplay(Option<PathBuf>),
+    TraceReplayEvent(TraceEvent),
+    TraceReplayFinished,
     LoadTextureIntegrated(String, Vec<u8>),
     LoadTexture(RelativePathBuf, PathBuf),
     CheckDataSourceUpdates,
@@ -1360,8 +2404,22 @@ pub enum ControllerEvent {
     TimerEnable(String),
     #[allow(dead_code)]
     TimerDisable(String),
-    TimerReset,
+    TimerReset(Option<String>),
     #[strum(to_string = "Toggled {0}")]
     TimerToggle(String),
+    #[strum(to_string = "Override for {0}")]
+    TimerOverride(String, TimerOverrideChange),
+    #[strum(to_string = "Clear attempts for {0}")]
+    ClearAttempts(String),
     Quit,
 }
+
+impl ControllerEvent {
+    /// Whether a source may be installed, updated or removed, moving the directories to watch.
+    fn changes_sources(&self) -> bool {
+        matches!(
+            self,
+            Self::DoDataSourceUpdate { .. } | Self::UninstallAddon(_)
+        )
+    }
+}
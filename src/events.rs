@@ -0,0 +1,159 @@
+//! Events raised on the Nexus event bus for other addons to react to, and those Taimi
+//! listens for in turn. The payloads are `#[repr(C)]` and only ever grow at the end.
+//!
+//! Strings in outgoing payloads are NUL-terminated UTF-8, and are only valid for the
+//! duration of the subscriber's callback, so they must be copied to be kept.
+
+use {
+    crate::{
+        controller::{Controller, ControllerEvent},
+        timer::{self, TextAlert, TimerFile},
+    },
+    nexus::event::{event_consume, Event},
+    std::ffi::{c_char, CStr, CString},
+};
+
+#[cfg(feature = "markers")]
+use crate::marker::format::MarkerSet;
+
+/// Raised as a timer phase starts, finishes or is reset, with a [`PhaseChanged`].
+pub const EV_TAIMI_PHASE_CHANGED: Event<PhaseChanged> =
+    unsafe { Event::new("EV_TAIMI_PHASE_CHANGED") };
+/// Raised as a text alert is shown, with an [`AlertFired`].
+pub const EV_TAIMI_ALERT: Event<AlertFired> = unsafe { Event::new("EV_TAIMI_ALERT") };
+/// Raised once a marker set has been placed, with a [`MarkersPlaced`].
+pub const EV_TAIMI_MARKERS_PLACED: Event<MarkersPlaced> =
+    unsafe { Event::new("EV_TAIMI_MARKERS_PLACED") };
+
+/// Resets the timer with the ID given as a NUL-terminated string, or every timer if null or empty.
+pub const EV_TAIMI_RESET_TIMER: Event<c_char> = unsafe { Event::new("EV_TAIMI_RESET_TIMER") };
+/// Presses or releases one of the timer key triggers, with a [`KeyTrigger`].
+pub const EV_TAIMI_KEY_TRIGGER: Event<KeyTrigger> = unsafe { Event::new("EV_TAIMI_KEY_TRIGGER") };
+/// Places the marker set with the ID given as a NUL-terminated string, as shown in the markers tab.
+pub const EV_TAIMI_PLACE_MARKERS: Event<c_char> = unsafe { Event::new("EV_TAIMI_PLACE_MARKERS") };
+
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseChange {
+    Started = 0,
+    Finished = 1,
+    Reset = 2,
+}
+
+impl From<timer::PhaseChange> for PhaseChange {
+    fn from(change: timer::PhaseChange) -> Self {
+        match change {
+            timer::PhaseChange::Started => Self::Started,
+            timer::PhaseChange::Finished => Self::Finished,
+            timer::PhaseChange::Reset => Self::Reset,
+        }
+    }
+}
+
+#[repr(C)]
+pub struct PhaseChanged {
+    pub timer_id: *const c_char,
+    pub timer_name: *const c_char,
+    pub phase_name: *const c_char,
+    /// Which of the timer's phases this is, counting from zero.
+    pub phase_index: u32,
+    pub change: PhaseChange,
+}
+
+#[repr(C)]
+pub struct AlertFired {
+    pub timer_id: *const c_char,
+    pub message: *const c_char,
+    /// 0 for low, 1 for normal, 2 for high and 3 for critical.
+    pub priority: u32,
+    pub duration_ms: u32,
+}
+
+#[repr(C)]
+pub struct MarkersPlaced {
+    pub id: *const c_char,
+    pub name: *const c_char,
+    pub map_id: u32,
+    pub marker_count: u32,
+}
+
+#[repr(C)]
+pub struct KeyTrigger {
+    /// From 0 to 4, the same as the key trigger keybinds.
+    pub index: u32,
+    pub released: bool,
+}
+
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap_or_default()
+}
+
+pub fn phase_changed(timer: &TimerFile, phase_name: &str, phase_index: usize, change: PhaseChange) {
+    let timer_id = c_string(&timer.id);
+    let timer_name = c_string(&timer.name());
+    let phase_name = c_string(phase_name);
+    EV_TAIMI_PHASE_CHANGED.raise(&PhaseChanged {
+        timer_id: timer_id.as_ptr(),
+        timer_name: timer_name.as_ptr(),
+        phase_name: phase_name.as_ptr(),
+        phase_index: phase_index as u32,
+        change,
+    });
+}
+
+pub fn alert_fired(alert: &TextAlert) {
+    let timer_id = c_string(&alert.timer.id);
+    let message = c_string(&alert.message);
+    EV_TAIMI_ALERT.raise(&AlertFired {
+        timer_id: timer_id.as_ptr(),
+        message: message.as_ptr(),
+        priority: alert.priority as u32,
+        duration_ms: alert.duration.as_millis().min(u32::MAX as u128) as u32,
+    });
+}
+
+#[cfg(feature = "markers")]
+pub fn markers_placed(markers: &MarkerSet) {
+    let id = c_string(&markers.id());
+    let name = c_string(&markers.name);
+    EV_TAIMI_MARKERS_PLACED.raise(&MarkersPlaced {
+        id: id.as_ptr(),
+        name: name.as_ptr(),
+        map_id: markers.map_id,
+        marker_count: markers.markers.len() as u32,
+    });
+}
+
+fn read_string(s: Option<&c_char>) -> Option<String> {
+    let s = unsafe { CStr::from_ptr(s? as *const c_char) };
+    Some(s.to_string_lossy().to_string()).filter(|s| !s.is_empty())
+}
+
+pub fn subscribe() {
+    EV_TAIMI_RESET_TIMER
+        .subscribe(event_consume!(<c_char> |id| {
+            Controller::try_send(ControllerEvent::TimerReset(read_string(id)));
+        }))
+        .revert_on_unload();
+
+    EV_TAIMI_KEY_TRIGGER
+        .subscribe(event_consume!(<KeyTrigger> |trigger| {
+            match trigger {
+                Some(trigger) if trigger.index < 5 => {
+                    Controller::try_send(ControllerEvent::TimerKeyIndex(trigger.index, trigger.released));
+                },
+                Some(trigger) => log::warn!("Ignoring a key trigger for index {}", trigger.index),
+                None => (),
+            }
+        }))
+        .revert_on_unload();
+
+    #[cfg(feature = "markers")]
+    EV_TAIMI_PLACE_MARKERS
+        .subscribe(event_consume!(<c_char> |id| {
+            if let Some(id) = read_string(id) {
+                Controller::try_send(ControllerEvent::SetMarkerId(id));
+            }
+        }))
+        .revert_on_unload();
+}
@@ -1,8 +1,11 @@
+mod audio;
 mod controller;
+mod events;
 mod render;
 mod settings;
-mod timer;
+mod trace;
 mod util;
+mod watcher;
 
 #[cfg(feature = "markers")]
 mod marker;
@@ -10,6 +13,9 @@ mod marker;
 #[cfg(feature = "space")]
 mod space;
 
+#[cfg(feature = "api")]
+mod api;
+
 //use i18n_embed_fl::fl;
 #[cfg(feature = "space")]
 use {
@@ -20,8 +26,11 @@ use {
         sync::atomic::{AtomicBool, Ordering},
     },
 };
+#[cfg(feature = "api")]
+use api::ApiEvent;
 use {
     crate::{
+        audio::SoundEvent,
         controller::{Controller, ControllerEvent},
         render::{RenderEvent, RenderState},
         settings::SettingsLock,
@@ -57,12 +66,13 @@ use {
     rust_embed::RustEmbed,
     settings::SourcesFile,
     std::{
-        collections::HashMap,
+        collections::{BTreeSet, HashMap},
         ffi::{c_char, CStr},
         ptr,
         sync::{Arc, LazyLock, Mutex, OnceLock, RwLock},
         thread::{self, JoinHandle},
     },
+    taimi_timer as timer,
     tokio::sync::mpsc::{channel, Sender},
     unic_langid_impl::LanguageIdentifier,
 };
@@ -113,8 +123,11 @@ pub mod built_info {
 #[cfg(feature = "space")]
 static TEXTURES: OnceLock<RwLock<HashMap<PathBuf, Arc<Texture>>>> = OnceLock::new();
 static IMGUI_TEXTURES: OnceLock<RwLock<HashMap<String, Arc<NexusTexture>>>> = OnceLock::new();
+/// Textures asked of the controller, so that each is only loaded once however often it's drawn.
+static REQUESTED_TEXTURES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
 static CONTROLLER_SENDER: RwLock<Option<Sender<ControllerEvent>>> = RwLock::new(None);
 static RENDER_SENDER: RwLock<Option<Sender<RenderEvent>>> = RwLock::new(None);
+static SOUND_SENDER: RwLock<Option<Sender<SoundEvent>>> = RwLock::new(None);
 static RENDER_CALLBACK: Mutex<Option<Revertible>> = Mutex::new(None);
 static ACCOUNT_NAME_CELL: OnceLock<String> = OnceLock::new();
 
@@ -122,6 +135,11 @@ static ACCOUNT_NAME_CELL: OnceLock<String> = OnceLock::new();
 static SPACE_SENDER: RwLock<Option<Sender<SpaceEvent>>> = RwLock::new(None);
 
 static CONTROLLER_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+static SOUND_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+#[cfg(feature = "api")]
+static API_SENDER: RwLock<Option<Sender<ApiEvent>>> = RwLock::new(None);
+#[cfg(feature = "api")]
+static API_THREAD: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
 
 nexus::export! {
     name: "TaimiHUD",
@@ -193,15 +211,29 @@ fn load() {
 
     let (controller_sender, controller_receiver) = channel::<ControllerEvent>(32);
     let (render_sender, render_receiver) = channel::<RenderEvent>(32);
+    let (sound_sender, sound_receiver) = channel::<SoundEvent>(32);
+
+    // the controller configures the API server once settings are loaded, so it has to be up first
+    #[cfg(feature = "api")]
+    {
+        let (api_sender, api_receiver) = channel::<ApiEvent>(32);
+        let api_handler = thread::spawn(move || api::load(api_receiver));
+        *API_THREAD.lock().unwrap() = Some(api_handler);
+        *API_SENDER.write().unwrap() = Some(api_sender);
+    }
 
     let controller_handler = {
         let render_sender = render_sender.clone();
         thread::spawn(move || Controller::load(controller_receiver, render_sender, addon_dir))
     };
 
+    let sound_handler = thread::spawn(move || audio::load(sound_receiver));
+
     // muh queues
     *CONTROLLER_THREAD.lock().unwrap() = Some(controller_handler);
     *CONTROLLER_SENDER.write().unwrap() = Some(controller_sender);
+    *SOUND_THREAD.lock().unwrap() = Some(sound_handler);
+    *SOUND_SENDER.write().unwrap() = Some(sound_sender);
 
     *RENDER_STATE.lock().unwrap() = Some(RenderState::new(render_receiver));
     *RENDER_SENDER.write().unwrap() = Some(render_sender);
@@ -258,6 +290,29 @@ fn load() {
         .revert_on_unload();
     }
 
+    let trace_record_keybind_handler = keybind_handler!(|_id, is_release| {
+        if !is_release {
+            Controller::try_send(ControllerEvent::TraceRecordToggle);
+        }
+    });
+    register_keybind_with_string(
+        fl!("trace-record-toggle"),
+        trace_record_keybind_handler,
+        "",
+    )
+    .revert_on_unload();
+
+    #[cfg(feature = "markers")]
+    {
+        let agent_marker_keybind_handler = keybind_handler!(|_id, is_release| {
+            if !is_release {
+                Controller::try_send(ControllerEvent::AgentPresetStep(true));
+            }
+        });
+        register_keybind_with_string(fl!("agent-marker-next"), agent_marker_keybind_handler, "")
+            .revert_on_unload();
+    }
+
     // Disused currently, icon loading for quick access
     /*
     let receive_texture =
@@ -319,11 +374,22 @@ fn load() {
             if let Some(evt) = combat_data.event() {
                 if let Some(agt) = combat_data.src() {
                     let agt = AgentOwned::from(unsafe { ptr::read(agt) });
+                    let dst = combat_data
+                        .dst()
+                        .map(|dst| AgentOwned::from(unsafe { ptr::read(dst) }));
                     Controller::try_send(ControllerEvent::CombatEvent {
                         src: agt,
+                        dst,
                         evt: evt.clone(),
                     });
                 }
+            } else if let Some(src) = combat_data.src() {
+                // without an event, arcdps is telling us about an agent or a new target
+                let src = AgentOwned::from(unsafe { ptr::read(src) });
+                let dst = combat_data
+                    .dst()
+                    .map(|dst| AgentOwned::from(unsafe { ptr::read(dst) }));
+                Controller::try_send(ControllerEvent::AgentTracking { src, dst });
             }
         }
     });
@@ -386,6 +452,8 @@ fn load() {
         )
     ).revert_on_unload();
 
+    events::subscribe();
+
     pub const EV_LANGUAGE_CHANGED: Event<()> = unsafe { Event::new("EV_LANGUAGE_CHANGED") };
 
     // I don't want to store the localization data in either Nexus or communicate it with Nexus,
@@ -506,6 +574,25 @@ fn unload() {
         None => (),
     }
 
+    // the sound scheduler stops once its queue closes
+    drop(SOUND_SENDER.write().unwrap().take());
+    if let Some(handle) = SOUND_THREAD.lock().unwrap().take() {
+        if let Err(e) = handle.join() {
+            log_join_error("sound", e);
+        }
+    }
+
+    // as does the API server, taking its clients with it
+    #[cfg(feature = "api")]
+    {
+        drop(API_SENDER.write().unwrap().take());
+        if let Some(handle) = API_THREAD.lock().unwrap().take() {
+            if let Err(e) = handle.join() {
+                log_join_error("api", e);
+            }
+        }
+    }
+
     if let Some(revert_render) = RENDER_CALLBACK.lock().unwrap().take() {
         revert_render();
     }
@@ -0,0 +1,238 @@
+use {
+    crate::{controller::SquadRoleState, marker::format::MarkerType},
+    serde::{Deserialize, Serialize},
+    std::{collections::HashSet, path::Path, sync::Arc},
+    tokio::fs::read_to_string,
+};
+
+/// Who an agent marker goes on. Only one member can hold each marker, so a role or
+/// subgroup picks the first of its members that hasn't been given a marker already.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AgentSelector {
+    Account(String),
+    Role(SquadRoleState),
+    /// Counting from 1, as in the squad window.
+    Subgroup(u8),
+}
+
+impl AgentSelector {
+    pub fn matches(&self, member: &SquadMember) -> bool {
+        match self {
+            Self::Account(account) => member.account_name.eq_ignore_ascii_case(account),
+            Self::Role(role) => member.role == *role,
+            Self::Subgroup(subgroup) => member.subgroup == Some(*subgroup),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentAssignment {
+    pub marker: MarkerType,
+    #[serde(flatten)]
+    pub target: AgentSelector,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub assignments: Vec<AgentAssignment>,
+}
+
+impl AgentPreset {
+    /**
+     * Matches each assignment against the squad in order, giving every member at most
+     * one marker. Members are tried by subgroup and then account name, so that the
+     * same squad always resolves the same way.
+     */
+    pub fn resolve(&self, squad: &[SquadMember]) -> Vec<AgentStep> {
+        let mut squad: Vec<&SquadMember> = squad.iter().collect();
+        squad.sort_by(|a, b| {
+            (a.subgroup.is_none(), a.subgroup, &a.account_name).cmp(&(
+                b.subgroup.is_none(),
+                b.subgroup,
+                &b.account_name,
+            ))
+        });
+        let mut assigned = HashSet::new();
+        self.assignments
+            .iter()
+            .map(|assignment| {
+                let member = squad
+                    .iter()
+                    .find(|member| {
+                        !assigned.contains(&member.account_name)
+                            && assignment.target.matches(member)
+                    })
+                    .map(|member| (*member).clone());
+                if let Some(member) = &member {
+                    assigned.insert(member.account_name.clone());
+                }
+                AgentStep {
+                    marker: assignment.marker.clone(),
+                    target: assignment.target.clone(),
+                    member,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The agent marker presets, kept alongside the marker files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AgentPresetFile {
+    #[serde(default)]
+    pub presets: Vec<AgentPreset>,
+}
+
+impl AgentPresetFile {
+    pub const FILENAME: &str = "agent_presets.json";
+
+    pub async fn load(markers_dir: &Path) -> anyhow::Result<Vec<Arc<AgentPreset>>> {
+        let path = markers_dir.join(Self::FILENAME);
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let mut file_data = read_to_string(&path).await?;
+        json_strip_comments::strip(&mut file_data)?;
+        let file: Self = serde_json::from_str(&file_data)?;
+        Ok(file.presets.into_iter().map(Arc::new).collect())
+    }
+}
+
+/// A squad member as far as the RTAPI or Unofficial Extras can tell us.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SquadMember {
+    pub account_name: String,
+    pub character_name: Option<String>,
+    /// Counting from 1, and None if they aren't in a subgroup.
+    pub subgroup: Option<u8>,
+    pub role: SquadRoleState,
+}
+
+impl SquadMember {
+    pub fn display_name(&self) -> String {
+        match &self.character_name {
+            Some(character) if !character.is_empty() => {
+                format!("{character} ({})", self.account_name)
+            }
+            _ => self.account_name.clone(),
+        }
+    }
+}
+
+/// One assignment of a preset being applied, and who it was resolved to, if anyone.
+#[derive(Debug, Clone)]
+pub struct AgentStep {
+    pub marker: MarkerType,
+    pub target: AgentSelector,
+    pub member: Option<SquadMember>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(account_name: &str, subgroup: Option<u8>, role: SquadRoleState) -> SquadMember {
+        SquadMember {
+            account_name: account_name.to_string(),
+            character_name: None,
+            subgroup,
+            role,
+        }
+    }
+
+    fn squad() -> Vec<SquadMember> {
+        use SquadRoleState::*;
+        vec![
+            member("Zed.1234", Some(2), Commander),
+            member("Cara.3456", None, Member),
+            member("Dan.7890", Some(1), Member),
+            member("Bea.5678", Some(1), Lieutenant),
+        ]
+    }
+
+    fn preset(assignments: Vec<(MarkerType, AgentSelector)>) -> AgentPreset {
+        AgentPreset {
+            name: "Preset".to_string(),
+            description: String::new(),
+            assignments: assignments
+                .into_iter()
+                .map(|(marker, target)| AgentAssignment { marker, target })
+                .collect(),
+        }
+    }
+
+    fn accounts(steps: &[AgentStep]) -> Vec<Option<&str>> {
+        steps
+            .iter()
+            .map(|step| step.member.as_ref().map(|m| m.account_name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn selectors_find_their_members() {
+        let preset = preset(vec![
+            (
+                MarkerType::Arrow,
+                AgentSelector::Role(SquadRoleState::Lieutenant),
+            ),
+            (MarkerType::Circle, AgentSelector::Subgroup(2)),
+            (
+                MarkerType::Heart,
+                AgentSelector::Account("cara.3456".to_string()),
+            ),
+            (
+                MarkerType::Square,
+                AgentSelector::Account("Nobody.0000".to_string()),
+            ),
+        ]);
+        let steps = preset.resolve(&squad());
+        assert_eq!(
+            accounts(&steps),
+            [Some("Bea.5678"), Some("Zed.1234"), Some("Cara.3456"), None]
+        );
+    }
+
+    #[test]
+    fn subgroups_count_from_one() {
+        let preset = preset(vec![
+            (MarkerType::Arrow, AgentSelector::Subgroup(1)),
+            (MarkerType::Circle, AgentSelector::Subgroup(0)),
+        ]);
+        let steps = preset.resolve(&squad());
+        assert_eq!(accounts(&steps), [Some("Bea.5678"), None]);
+    }
+
+    #[test]
+    fn members_get_one_marker_each_in_a_stable_order() {
+        let preset = preset(vec![
+            (
+                MarkerType::Arrow,
+                AgentSelector::Role(SquadRoleState::Commander),
+            ),
+            (MarkerType::Circle, AgentSelector::Subgroup(2)),
+            (MarkerType::Heart, AgentSelector::Subgroup(1)),
+            (MarkerType::Square, AgentSelector::Subgroup(1)),
+            (
+                MarkerType::Star,
+                AgentSelector::Role(SquadRoleState::Member),
+            ),
+        ]);
+        let expected = [
+            Some("Zed.1234"),
+            None,
+            Some("Bea.5678"),
+            Some("Dan.7890"),
+            Some("Cara.3456"),
+        ];
+        let mut squad = squad();
+        assert_eq!(accounts(&preset.resolve(&squad)), expected);
+        squad.reverse();
+        assert_eq!(accounts(&preset.resolve(&squad)), expected);
+    }
+}
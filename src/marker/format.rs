@@ -25,7 +25,9 @@ use {
         io::AsyncWriteExt,
         sync::Semaphore,
         task::JoinSet,
+        time::Duration,
     },
+    uuid::Uuid,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -40,6 +42,151 @@ pub enum MarkerFormats {
     Taimi(Vec<MarkerSet>),
 }
 
+impl MarkerFormats {
+    pub fn filetype(&self) -> MarkerFiletype {
+        match self {
+            Self::Community(_) => MarkerFiletype::Community,
+            Self::Integrated(_) => MarkerFiletype::Integrated,
+            Self::Taimi(_) => MarkerFiletype::Taimi,
+        }
+    }
+
+    /**
+     * Rewrites the file as another format. Whatever the new format has no place for is
+     * kept in the Taimi extensions, so that converting back gives the same file, other
+     * than sets being grouped by category in a Community file.
+     */
+    pub fn convert(self, to: &MarkerFiletype) -> Self {
+        if self.filetype() == *to {
+            return self;
+        }
+        let (info, marker_sets) = self.into_parts();
+        Self::from_parts(to, info, marker_sets)
+    }
+
+    /// The marker sets with their categories filled in, and the rest of the file.
+    fn into_parts(self) -> (MarkerFileInfo, Vec<MarkerSet>) {
+        match self {
+            Self::Community(f) => {
+                let info = MarkerFileInfo {
+                    last_edit: Some(f.last_edit).filter(|_| !f.last_edit_generated),
+                    version: f.version,
+                    categories: f.categories.iter().map(|c| c.name.clone()).collect(),
+                };
+                let marker_sets = f
+                    .categories
+                    .into_iter()
+                    .flat_map(|category| {
+                        let name = category.name;
+                        category.marker_sets.into_iter().map(move |mut ms| {
+                            if name != NO_CATEGORY {
+                                ms.category = Some(name.clone());
+                            }
+                            ms
+                        })
+                    })
+                    .collect();
+                (info, marker_sets)
+            }
+            Self::Integrated(c) => {
+                let info = MarkerFileInfo {
+                    last_edit: c.last_edit,
+                    version: Some(c.version).filter(|v| v != INTEGRATED_VERSION),
+                    categories: c.categories,
+                };
+                (info, c.squad_marker_preset)
+            }
+            Self::Taimi(mut t) => {
+                let info = t
+                    .iter_mut()
+                    .find_map(|ms| ms.file_info.take())
+                    .unwrap_or_default();
+                for ms in &mut t {
+                    ms.file_info = None;
+                }
+                (info, t)
+            }
+        }
+    }
+
+    fn from_parts(
+        filetype: &MarkerFiletype,
+        mut info: MarkerFileInfo,
+        mut marker_sets: Vec<MarkerSet>,
+    ) -> Self {
+        // only worth keeping when the sets alone wouldn't give the same categories back
+        if info.categories == category_order(&marker_sets) {
+            info.categories.clear();
+        }
+        match filetype {
+            MarkerFiletype::Community => {
+                let mut categories: Vec<MarkerCategory> = info
+                    .categories
+                    .into_iter()
+                    .map(|name| MarkerCategory {
+                        name,
+                        marker_sets: Vec::new(),
+                    })
+                    .collect();
+                for mut ms in marker_sets {
+                    let name = ms.category.clone().unwrap_or(NO_CATEGORY.to_string());
+                    // the category holding the set says it all, unless it was left out
+                    if name != NO_CATEGORY {
+                        ms.category = None;
+                    }
+                    match categories.iter_mut().find(|c| c.name == name) {
+                        Some(category) => category.marker_sets.push(ms),
+                        None => categories.push(MarkerCategory {
+                            name,
+                            marker_sets: vec![ms],
+                        }),
+                    }
+                }
+                Self::Community(MarkerFile {
+                    last_edit: info.last_edit.unwrap_or_else(Utc::now),
+                    last_edit_generated: info.last_edit.is_none(),
+                    version: info.version,
+                    path: None,
+                    categories,
+                })
+            }
+            MarkerFiletype::Integrated => Self::Integrated(IntegratedMarkers {
+                version: info.version.unwrap_or(INTEGRATED_VERSION.to_string()),
+                last_edit: info.last_edit,
+                categories: info.categories,
+                path: None,
+                squad_marker_preset: marker_sets,
+            }),
+            MarkerFiletype::Taimi => {
+                if info != MarkerFileInfo::default() {
+                    if let Some(ms) = marker_sets.first_mut() {
+                        ms.file_info = Some(info);
+                    }
+                }
+                Self::Taimi(marker_sets)
+            }
+        }
+    }
+}
+
+/// The categories a Community file would be given for these sets, in order.
+fn category_order(marker_sets: &[MarkerSet]) -> Vec<String> {
+    let mut categories: Vec<String> = Vec::new();
+    for ms in marker_sets {
+        let name = ms.category.as_deref().unwrap_or(NO_CATEGORY);
+        if !categories.iter().any(|c| c == name) {
+            categories.push(name.to_string());
+        }
+    }
+    categories
+}
+
+/// Where marker sets without a category go in a Community file.
+const NO_CATEGORY: &str = "No category";
+
+/// The version the Commander's Markers module writes integrated files with.
+const INTEGRATED_VERSION: &str = "2.0.0";
+
 #[derive(Debug, PartialEq, Eq, EnumIter, Display, Clone)]
 pub enum MarkerFiletype {
     Community,
@@ -131,6 +278,10 @@ impl RuntimeMarkers {
         Ok(data)
     }
 
+    pub async fn load(path: &PathBuf) -> anyhow::Result<Arc<Self>> {
+        Ok(Arc::new(Self::load_arcless(path).await?))
+    }
+
     pub async fn create_file(&self, path: &PathBuf) -> anyhow::Result<()> {
         log::debug!("MarkerFormat: Saving to \"{}\".", path.display());
         let settings_str = serde_json::to_string(&self.file)?;
@@ -140,8 +291,16 @@ impl RuntimeMarkers {
         Ok(())
     }
 
-    pub async fn save(&self, path: &PathBuf) -> anyhow::Result<()> {
+    /**
+     * Files are only ever written back when they're edited in Taimi, so that's also when
+     * their marker sets without a uuid get one. Until then they keep their legacy ids.
+     */
+    pub async fn save(&mut self, path: &PathBuf) -> anyhow::Result<()> {
         log::debug!("MarkerFormat: Saving to \"{}\".", path.display());
+        let assigned = self.assign_uuids();
+        if assigned > 0 {
+            log::info!("Gave {assigned} marker sets in \"{path:?}\" a uuid.");
+        }
         let settings_str = serde_json::to_string(&self.file)?;
         let mut file = OpenOptions::new()
             .write(true)
@@ -182,6 +341,20 @@ impl RuntimeMarkers {
         Ok(())
     }
 
+    pub async fn convert_file(path: &PathBuf, to: MarkerFiletype) -> anyhow::Result<()> {
+        let file = Self::load_arcless(path).await?;
+        log::info!(
+            "Converting the markers file at \"{path:?}\" from {} to {to}.",
+            file.file.filetype()
+        );
+        let mut file = Self {
+            path: file.path,
+            file: file.file.convert(&to),
+        };
+        file.save(path).await?;
+        Ok(())
+    }
+
     pub async fn get_entry(
         &mut self,
         path: &PathBuf,
@@ -226,8 +399,25 @@ impl RuntimeMarkers {
         }
     }
 
-    pub async fn load(path: &PathBuf) -> anyhow::Result<Arc<Self>> {
-        Ok(Arc::new(Self::load_arcless(path).await?))
+    /// Gives every marker set without a uuid a new one, returning how many were missing.
+    pub fn assign_uuids(&mut self) -> usize {
+        let marker_sets: Vec<&mut MarkerSet> = match &mut self.file {
+            MarkerFormats::Community(f) => f
+                .categories
+                .iter_mut()
+                .flat_map(|category| category.marker_sets.iter_mut())
+                .collect(),
+            MarkerFormats::Taimi(t) => t.iter_mut().collect(),
+            MarkerFormats::Integrated(c) => c.squad_marker_preset.iter_mut().collect(),
+        };
+        let mut assigned = 0;
+        for marker_set in marker_sets {
+            if marker_set.uuid.is_none() {
+                marker_set.uuid = Some(Uuid::new_v4());
+                assigned += 1;
+            }
+        }
+        assigned
     }
 
     pub async fn append_raw(&mut self, ms: MarkerSet) -> anyhow::Result<()> {
@@ -251,26 +441,25 @@ impl RuntimeMarkers {
                         }
                     };
                     category.marker_sets.push(ms);
-                    f.last_edit = Utc::now();
+                    f.touch();
                 } else {
-                    let category = match f.categories.iter_mut().find(|c| &c.name == "No category")
-                    {
+                    let category = match f.categories.iter_mut().find(|c| c.name == NO_CATEGORY) {
                         Some(c) => c,
                         None => {
                             f.categories.push(MarkerCategory {
-                                name: "No category".to_string(),
+                                name: NO_CATEGORY.to_string(),
                                 marker_sets: Vec::new(),
                             });
                             f.categories
                                 .iter_mut()
-                                .find(|c| &c.name == "No category")
+                                .find(|c| c.name == NO_CATEGORY)
                                 .ok_or(anyhow!(
                                     "Can't find the category \"No category\" we should've made"
                                 ))?
                         }
                     };
                     category.marker_sets.push(ms);
-                    f.last_edit = Utc::now();
+                    f.touch();
                 }
             }
             MarkerFormats::Taimi(t) => {
@@ -305,9 +494,11 @@ impl RuntimeMarkers {
             MarkerFiletype::Community => {
                 let file_data = MarkerFile {
                     last_edit: Utc::now(),
+                    last_edit_generated: false,
+                    version: None,
                     path: Some(path.clone()),
                     categories: vec![MarkerCategory {
-                        name: ms.category.clone().unwrap_or("No category".to_string()),
+                        name: ms.category.clone().unwrap_or(NO_CATEGORY.to_string()),
                         marker_sets: vec![ms],
                     }],
                 };
@@ -327,7 +518,9 @@ impl RuntimeMarkers {
             }
             MarkerFiletype::Integrated => {
                 let file_data = IntegratedMarkers {
-                    version: "2.0.0".to_string(),
+                    version: INTEGRATED_VERSION.to_string(),
+                    last_edit: None,
+                    categories: Vec::new(),
                     path: Some(path.clone()),
                     squad_marker_preset: vec![ms],
                 };
@@ -364,7 +557,7 @@ impl RuntimeMarkers {
                         let category_name = marker_set
                             .category
                             .clone()
-                            .unwrap_or("No category".to_string());
+                            .unwrap_or(NO_CATEGORY.to_string());
                         let entry = finalized.entry(category_name.clone()).or_default();
                         let mut marker_set_data = marker_set.clone();
                         marker_set_data.category = Some(category_name.clone());
@@ -400,15 +593,36 @@ impl RuntimeMarkers {
 #[serde(rename_all = "camelCase")]
 pub struct MarkerFile {
     pub last_edit: DateTime<Utc>,
+    /// Set when `last_edit` was made up for a file converted from one without it, so
+    /// that converting back leaves it out again.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub last_edit_generated: bool,
+    /// Kept from an integrated file this was converted from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
     #[serde(skip, default)]
     pub path: Option<PathBuf>,
     pub categories: Vec<MarkerCategory>,
 }
 
+impl MarkerFile {
+    fn touch(&mut self) {
+        self.last_edit = Utc::now();
+        self.last_edit_generated = false;
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct IntegratedMarkers {
     pub version: String,
+    /// Kept from a Community file this was converted from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_edit: Option<DateTime<Utc>>,
+    /// Kept from a Community file this was converted from, for its empty categories and
+    /// their order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
     #[serde(skip, default)]
     pub path: Option<PathBuf>,
     pub squad_marker_preset: Vec<MarkerSet>,
@@ -422,13 +636,75 @@ pub struct MarkerCategory {
     pub marker_sets: Vec<MarkerSet>,
 }
 
+/**
+ * The parts of a Community or integrated file besides its marker sets. A Taimi file has
+ * nowhere else to keep them, so when one is converted to, they go on its first set, and
+ * are lost if it has none.
+ */
+#[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerFileInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_edit: Option<DateTime<Utc>>,
+    /// Only when it isn't the version integrated files are normally written with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+}
+
 fn default_true() -> bool {
     true
 }
 
+/// How close the player has to come to a marker set's `trigger` when it doesn't say otherwise.
+pub const DEFAULT_TRIGGER_RADIUS: f32 = 15.0;
+
+/**
+ * When a marker set is placed automatically, in place of the default sphere around its
+ * `trigger`. The shape is centred on, or for a box has a corner at, the `trigger`.
+ */
+#[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoplaceTrigger {
+    pub radius: Option<OrderedFloat<f32>>,
+    /// The corner of a box opposite the `trigger`, which takes precedence over `radius`.
+    pub antipode: Option<MarkerPosition>,
+    #[serde(default)]
+    pub require_combat: bool,
+    #[serde(default)]
+    pub require_out_of_combat: bool,
+    /// Counting the player, who is a squad of one on their own.
+    pub min_squad_size: Option<u32>,
+    #[serde(default)]
+    pub rearm: RearmPolicy,
+}
+
+/// When a marker set that has been placed automatically may be placed again, besides on
+/// changing maps.
+#[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RearmPolicy {
+    #[default]
+    Never,
+    /// Once the player is this much further away than the trigger reaches.
+    #[serde(rename_all = "camelCase")]
+    OnLeave {
+        hysteresis: OrderedFloat<f32>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Cooldown {
+        seconds: OrderedFloat<f32>,
+    },
+    CombatExit,
+}
+
 #[derive(Hash, Eq, PartialEq, Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MarkerSet {
+    /// Generated when the set is created or first loaded, so settings survive edits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
     #[serde(default = "default_true")]
     pub enabled: bool,
     pub category: Option<String>,
@@ -437,7 +713,11 @@ pub struct MarkerSet {
     pub description: String,
     pub map_id: u32,
     pub trigger: MarkerPosition,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autoplace: Option<AutoplaceTrigger>,
     pub markers: Vec<MarkerEntry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_info: Option<MarkerFileInfo>,
     #[serde(default, skip)]
     pub path: Option<PathBuf>,
     #[serde(default, skip)]
@@ -446,6 +726,14 @@ pub struct MarkerSet {
 
 impl MarkerSet {
     pub fn id(&self) -> String {
+        match &self.uuid {
+            Some(uuid) => uuid.to_string(),
+            None => self.legacy_id(),
+        }
+    }
+
+    /// The id settings were kept under before marker sets had uuids.
+    pub fn legacy_id(&self) -> String {
         let mut pieces = Vec::new();
         if let Some(category) = &self.category {
             pieces.push(category.clone());
@@ -465,7 +753,11 @@ impl MarkerSet {
     pub fn status(&self) -> bool {
         let settings = SETTINGS.get().unwrap();
         if let Ok(settings_lock) = settings.try_read() {
-            let result = if let Some(marker) = settings_lock.markers.get(&self.id()) {
+            let marker = settings_lock
+                .markers
+                .get(&self.id())
+                .or_else(|| settings_lock.markers.get(&self.legacy_id()));
+            let result = if let Some(marker) = marker {
                 !marker.disabled
             } else {
                 self.enabled
@@ -476,9 +768,75 @@ impl MarkerSet {
         }
     }
 
-    pub fn trigger(&self, pos: Vec3) -> bool {
-        let trig_vec: Vec3 = self.trigger.clone().into();
-        trig_vec.distance(pos) <= 15.0
+    pub fn trigger_polytope(&self) -> Polytope {
+        self.grown_trigger_polytope(0.0)
+    }
+
+    pub fn rearm_policy(&self) -> RearmPolicy {
+        self.autoplace
+            .as_ref()
+            .map(|autoplace| autoplace.rearm.clone())
+            .unwrap_or_default()
+    }
+
+    /// The trigger, grown outwards by `by` on every side.
+    fn grown_trigger_polytope(&self, by: f32) -> Polytope {
+        let trigger: Vec3 = self.trigger.clone().into();
+        let autoplace = self.autoplace.as_ref();
+        match (
+            autoplace.and_then(|a| a.antipode.clone()),
+            autoplace.and_then(|a| a.radius),
+        ) {
+            (Some(antipode), _) => {
+                let antipode: Vec3 = antipode.into();
+                Polytope::NCuboid {
+                    pode: (trigger.min(antipode) - by).into(),
+                    antipode: (trigger.max(antipode) + by).into(),
+                }
+            }
+            (None, radius) => Polytope::NSphere {
+                center: trigger.into(),
+                radius: radius.map_or(DEFAULT_TRIGGER_RADIUS, |radius| *radius) + by,
+            },
+        }
+    }
+
+    /// Whether a set spent `spent_for` ago may be placed automatically again.
+    pub fn rearms(&self, pos: Vec3, spent_for: Duration, left_combat: bool) -> bool {
+        match self.rearm_policy() {
+            RearmPolicy::Never => false,
+            RearmPolicy::OnLeave { hysteresis } => !self
+                .grown_trigger_polytope(*hysteresis)
+                .point_is_within(Position::Vec3(pos)),
+            RearmPolicy::Cooldown { seconds } => spent_for.as_secs_f32() >= *seconds,
+            RearmPolicy::CombatExit => left_combat,
+        }
+    }
+
+    /**
+     * Whether the set should be placed automatically for a player at `pos`. The squad size
+     * is only asked for when the set has a minimum. Spheres include their edge, as they
+     * always have for marker sets.
+     */
+    pub fn trigger(&self, pos: Vec3, in_combat: bool, squad_size: impl FnOnce() -> usize) -> bool {
+        if let Some(autoplace) = &self.autoplace {
+            if (autoplace.require_combat && !in_combat)
+                || (autoplace.require_out_of_combat && in_combat)
+            {
+                return false;
+            }
+            if autoplace
+                .min_squad_size
+                .is_some_and(|min| squad_size() < min as usize)
+            {
+                return false;
+            }
+        }
+        let pos = Position::Vec3(pos);
+        match self.trigger_polytope() {
+            Polytope::NSphere { center, radius } => center.distance(pos) <= radius,
+            polytope => polytope.point_is_within(pos),
+        }
     }
     pub fn combined(&self) -> String {
         if let Some(author) = &self.author {
@@ -541,7 +899,7 @@ impl From<MarkerPosition> for Polytope {
     fn from(local: MarkerPosition) -> Self {
         Polytope::NSphere {
             center: local.into(),
-            radius: 15.0,
+            radius: DEFAULT_TRIGGER_RADIUS,
         }
     }
 }
@@ -593,7 +951,6 @@ impl MarkerType {
         }
     }
 
-    #[allow(dead_code)]
     pub fn to_set_agent_gamebind(&self) -> GameBind {
         match self {
             Self::Blank => panic!("i can't believe you've done this"),
@@ -606,7 +963,234 @@ impl MarkerType {
             Self::Spiral => GameBind::SquadMarkerSetAgentSwirl,
             Self::Triangle => GameBind::SquadMarkerSetAgentTriangle,
             Self::Cross => GameBind::SquadMarkerSetAgentCross,
-            Self::ClearMarkers => GameBind::SquadMarkerClearAllWorld,
+            Self::ClearMarkers => GameBind::SquadMarkerClearAllAgent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::Value};
+
+    fn fixture(filetype: &MarkerFiletype) -> MarkerFormats {
+        let mut data = match filetype {
+            MarkerFiletype::Community => include_str!("fixtures/community.markers"),
+            MarkerFiletype::Integrated => include_str!("fixtures/integrated.markers"),
+            MarkerFiletype::Taimi => include_str!("fixtures/taimi.markers"),
+        }
+        .to_string();
+        json_strip_comments::strip(&mut data).unwrap();
+        serde_json::from_str(&data).unwrap()
+    }
+
+    /// Writes the file out and reads it back in, as saving and loading would.
+    fn rewrite(file: &MarkerFormats) -> MarkerFormats {
+        serde_json::from_str(&serde_json::to_string(file).unwrap()).unwrap()
+    }
+
+    fn to_value(file: &MarkerFormats) -> Value {
+        serde_json::to_value(file).unwrap()
+    }
+
+    fn position(x: f32, y: f32, z: f32) -> MarkerPosition {
+        MarkerPosition {
+            x: x.into(),
+            y: y.into(),
+            z: z.into(),
         }
     }
+
+    /// A set triggered at the origin.
+    fn marker_set(autoplace: Option<AutoplaceTrigger>) -> MarkerSet {
+        MarkerSet {
+            uuid: None,
+            enabled: true,
+            category: None,
+            author: None,
+            name: "Set".to_string(),
+            description: String::new(),
+            map_id: 1,
+            trigger: position(0.0, 0.0, 0.0),
+            autoplace,
+            markers: Vec::new(),
+            file_info: None,
+            path: None,
+            idx: None,
+        }
+    }
+
+    fn no_squad() -> usize {
+        panic!("asked for the squad size without a minimum");
+    }
+
+    #[test]
+    fn fixtures_are_read_as_their_formats() {
+        for filetype in MarkerFiletype::iter() {
+            assert_eq!(fixture(&filetype).filetype(), filetype);
+        }
+    }
+
+    #[test]
+    fn converting_there_and_back_changes_nothing() {
+        for from in MarkerFiletype::iter() {
+            for via in MarkerFiletype::iter() {
+                let original = fixture(&from);
+                let converted = rewrite(&original.clone().convert(&via));
+                assert_eq!(converted.filetype(), via, "{from} to {via}");
+                let back = rewrite(&converted.convert(&from));
+                assert_eq!(
+                    to_value(&back),
+                    to_value(&original),
+                    "{from} to {via} and back"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn community_details_are_kept_in_a_taimi_file() {
+        let MarkerFormats::Taimi(sets) =
+            fixture(&MarkerFiletype::Community).convert(&MarkerFiletype::Taimi)
+        else {
+            panic!("not converted to a Taimi file");
+        };
+        let info = sets[0].file_info.clone().unwrap();
+        assert_eq!(info.last_edit, "2025-03-14T18:30:00Z".parse().ok());
+        // the empty category wouldn't come back from the sets alone
+        assert_eq!(info.categories, ["Raids", "Strikes", "No category"]);
+        assert!(sets[1..].iter().all(|set| set.file_info.is_none()));
+        let categories: Vec<_> = sets.iter().map(|set| set.category.as_deref()).collect();
+        assert_eq!(categories, [Some("Raids"), Some("Raids"), None]);
+    }
+
+    #[test]
+    fn taimi_details_are_kept_in_an_integrated_file() {
+        let MarkerFormats::Integrated(integrated) =
+            fixture(&MarkerFiletype::Taimi).convert(&MarkerFiletype::Integrated)
+        else {
+            panic!("not converted to an integrated file");
+        };
+        assert_eq!(integrated.version, "1.1.0");
+        assert_eq!(integrated.last_edit, "2024-11-02T09:15:00Z".parse().ok());
+        assert_eq!(
+            integrated.categories,
+            ["Open world", "Meta events", "No category"]
+        );
+        assert!(integrated
+            .squad_marker_preset
+            .iter()
+            .all(|set| set.file_info.is_none()));
+    }
+
+    #[test]
+    fn a_made_up_last_edit_is_left_out_again() {
+        let MarkerFormats::Community(community) =
+            fixture(&MarkerFiletype::Integrated).convert(&MarkerFiletype::Community)
+        else {
+            panic!("not converted to a Community file");
+        };
+        assert!(community.last_edit_generated);
+        let MarkerFormats::Integrated(integrated) =
+            MarkerFormats::Community(community).convert(&MarkerFiletype::Integrated)
+        else {
+            panic!("not converted to an integrated file");
+        };
+        assert_eq!(integrated.last_edit, None);
+        assert_eq!(integrated.version, INTEGRATED_VERSION);
+    }
+
+    #[test]
+    fn trigger_defaults_to_a_sphere_that_includes_its_edge() {
+        let set = marker_set(None);
+        assert!(set.trigger(Vec3::ZERO, false, no_squad));
+        assert!(set.trigger(Vec3::new(0.0, DEFAULT_TRIGGER_RADIUS, 0.0), false, no_squad));
+        assert!(!set.trigger(Vec3::new(0.0, 15.1, 0.0), false, no_squad));
+    }
+
+    #[test]
+    fn trigger_is_a_box_out_to_the_antipode() {
+        let set = marker_set(Some(AutoplaceTrigger {
+            antipode: Some(position(-20.0, 4.0, 4.0)),
+            ..Default::default()
+        }));
+        assert!(set.trigger(Vec3::new(-18.0, 2.0, 2.0), false, no_squad));
+        // within the default sphere, but not the box
+        assert!(!set.trigger(Vec3::new(5.0, 0.0, 0.0), false, no_squad));
+        assert!(!set.trigger(Vec3::new(-10.0, 6.0, 2.0), false, no_squad));
+    }
+
+    #[test]
+    fn trigger_waits_for_the_combat_it_requires() {
+        let in_combat = marker_set(Some(AutoplaceTrigger {
+            require_combat: true,
+            ..Default::default()
+        }));
+        assert!(in_combat.trigger(Vec3::ZERO, true, no_squad));
+        assert!(!in_combat.trigger(Vec3::ZERO, false, no_squad));
+        let out_of_combat = marker_set(Some(AutoplaceTrigger {
+            require_out_of_combat: true,
+            ..Default::default()
+        }));
+        assert!(out_of_combat.trigger(Vec3::ZERO, false, no_squad));
+        assert!(!out_of_combat.trigger(Vec3::ZERO, true, no_squad));
+    }
+
+    #[test]
+    fn trigger_waits_for_a_big_enough_squad() {
+        let set = marker_set(Some(AutoplaceTrigger {
+            min_squad_size: Some(5),
+            ..Default::default()
+        }));
+        assert!(!set.trigger(Vec3::ZERO, false, || 4));
+        assert!(set.trigger(Vec3::ZERO, false, || 5));
+        // nor is the squad size asked for when only the radius is given
+        let set = marker_set(Some(AutoplaceTrigger {
+            radius: Some(5.0.into()),
+            ..Default::default()
+        }));
+        assert!(set.trigger(Vec3::ZERO, false, no_squad));
+    }
+
+    fn rearming(rearm: RearmPolicy) -> MarkerSet {
+        marker_set(Some(AutoplaceTrigger {
+            rearm,
+            ..Default::default()
+        }))
+    }
+
+    #[test]
+    fn never_rearms() {
+        let set = rearming(RearmPolicy::Never);
+        let far = Vec3::new(1000.0, 0.0, 0.0);
+        assert!(!set.rearms(far, Duration::from_secs(3600), true));
+    }
+
+    #[test]
+    fn rearms_once_left_by_the_hysteresis() {
+        let set = rearming(RearmPolicy::OnLeave {
+            hysteresis: 10.0.into(),
+        });
+        let spent_for = Duration::from_secs(3600);
+        assert!(!set.rearms(Vec3::ZERO, spent_for, true));
+        // out of the trigger, but still inside it once grown
+        assert!(!set.rearms(Vec3::new(20.0, 0.0, 0.0), spent_for, true));
+        assert!(set.rearms(Vec3::new(30.0, 0.0, 0.0), Duration::ZERO, false));
+    }
+
+    #[test]
+    fn rearms_after_the_cooldown() {
+        let set = rearming(RearmPolicy::Cooldown {
+            seconds: 60.0.into(),
+        });
+        assert!(!set.rearms(Vec3::ZERO, Duration::from_secs(59), true));
+        assert!(set.rearms(Vec3::ZERO, Duration::from_secs(60), false));
+    }
+
+    #[test]
+    fn rearms_on_leaving_combat() {
+        let set = rearming(RearmPolicy::CombatExit);
+        let far = Vec3::new(1000.0, 0.0, 0.0);
+        assert!(!set.rearms(far, Duration::from_secs(3600), false));
+        assert!(set.rearms(Vec3::ZERO, Duration::ZERO, true));
+    }
 }
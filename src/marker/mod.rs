@@ -1,2 +1,3 @@
+pub mod agent;
 pub mod atomic;
 pub mod format;
@@ -0,0 +1,68 @@
+use {
+    crate::timer::{TextAlert, TextAlertPriority, TimerFile},
+    std::sync::Arc,
+    tokio::time::Instant,
+};
+
+#[derive(Debug, Clone)]
+pub struct QueuedTextAlert {
+    pub message: String,
+    pub priority: TextAlertPriority,
+    pub since: Instant,
+    pub until: Instant,
+    /// Every timer currently asking for this message.
+    pub timers: Vec<Arc<TimerFile>>,
+}
+
+/**
+ * The text alerts that want to be on screen.
+ *
+ * Identical messages from overlapping timers share a single entry, which lasts as long
+ * as the longest of them and takes the highest priority asked for. When more alerts are
+ * due than fit, the most urgent win, and the oldest of those go first.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TextAlertQueue {
+    alerts: Vec<QueuedTextAlert>,
+}
+
+impl TextAlertQueue {
+    pub fn push(&mut self, alert: TextAlert) {
+        let now = Instant::now();
+        let until = now + alert.duration;
+        match self.alerts.iter_mut().find(|a| a.message == alert.message) {
+            Some(existing) => {
+                existing.until = existing.until.max(until);
+                existing.priority = existing.priority.max(alert.priority);
+                if !existing.timers.iter().any(|t| Arc::ptr_eq(t, &alert.timer)) {
+                    existing.timers.push(alert.timer);
+                }
+            }
+            None => self.alerts.push(QueuedTextAlert {
+                message: alert.message,
+                priority: alert.priority,
+                since: now,
+                until,
+                timers: vec![alert.timer],
+            }),
+        }
+    }
+
+    /// Withdraws `timer` from its alerts, dropping any no other timer still wants.
+    pub fn end(&mut self, timer: &Arc<TimerFile>) {
+        for alert in &mut self.alerts {
+            alert.timers.retain(|t| !Arc::ptr_eq(t, timer));
+        }
+        self.alerts.retain(|alert| !alert.timers.is_empty());
+    }
+
+    /// Drops expired alerts, returning at most `max` of the rest in display order.
+    pub fn visible(&mut self, max: usize) -> Vec<&QueuedTextAlert> {
+        let now = Instant::now();
+        self.alerts.retain(|alert| alert.until > now);
+        let mut visible: Vec<_> = self.alerts.iter().collect();
+        visible.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.since.cmp(&b.since)));
+        visible.truncate(max);
+        visible
+    }
+}
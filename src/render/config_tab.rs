@@ -4,7 +4,9 @@ use {
         controller::ProgressBarStyleChange,
         fl,
         render::TextFont,
+        timer::RemainingFormat,
         settings::{MarkerAutoPlaceSettings, SquadCondition},
+        util::UiExt,
         ControllerEvent, Controller, SETTINGS,
     },
     nexus::imgui::{ComboBox, Condition, Selectable, Slider, TreeNode, TreeNodeFlags, Ui},
@@ -15,6 +17,12 @@ pub struct ConfigTabState {
     pub katrender: bool,
     pub marker_autoplace: MarkerAutoPlaceSettings,
     pub marker_autoplace_inner: Option<SquadCondition>,
+    /// The port being typed in, until enter is pressed.
+    #[cfg(feature = "api")]
+    pub api_port: Option<i32>,
+    /// The comma separated origins being typed in, until enter is pressed.
+    #[cfg(feature = "api")]
+    pub api_allowed_origins: Option<String>,
 }
 
 impl ConfigTabState {
@@ -23,6 +31,10 @@ impl ConfigTabState {
             katrender: false,
             marker_autoplace: Default::default(),
             marker_autoplace_inner: Default::default(),
+            #[cfg(feature = "api")]
+            api_port: None,
+            #[cfg(feature = "api")]
+            api_allowed_origins: None,
         }
     }
 
@@ -105,9 +117,16 @@ impl ConfigTabState {
         };
         let timers_window_closure = || {
             ui.dummy([4.0, 4.0]);
+            let mut max_text_alerts = None;
             if let Some(settings) = SETTINGS.get().and_then(|settings| settings.try_read().ok()) {
                 timer_window_state.progress_bar.stock = settings.progress_bar.stock;
+                max_text_alerts = Some(settings.max_text_alerts);
             };
+            if let Some(mut max_text_alerts) = max_text_alerts {
+                if Slider::new(&fl!("max-text-alerts"), 1, 10).build(ui, &mut max_text_alerts) {
+                    Controller::try_send(ControllerEvent::MaxTextAlerts(max_text_alerts));
+                }
+            }
             if ui.checkbox(
                 &fl!("stock-imgui-progress-bar"),
                 &mut timer_window_state.progress_bar.stock,
@@ -156,6 +175,25 @@ impl ConfigTabState {
                 .preview_value(&timer_window_state.progress_bar.font.to_string())
                 .build(ui, font_closure)
             {}
+            let remaining_format_closure = || {
+                let mut selected = timer_window_state.progress_bar.remaining_format;
+                for remaining_format in RemainingFormat::iter() {
+                    if Selectable::new(remaining_format.to_string())
+                        .selected(remaining_format == selected)
+                        .build(ui)
+                    {
+                        Controller::try_send(ControllerEvent::ProgressBarStyle(
+                            ProgressBarStyleChange::RemainingFormat(remaining_format),
+                        ));
+                        selected = remaining_format;
+                    }
+                }
+                selected
+            };
+            if let Some(_selection) = ComboBox::new(&fl!("remaining-format"))
+                .preview_value(&timer_window_state.progress_bar.remaining_format.to_string())
+                .build(ui, remaining_format_closure)
+            {}
         };
         let _timers_window = TreeNode::new(&fl!("timer-window"))
             .flags(TreeNodeFlags::FRAMED)
@@ -167,5 +205,65 @@ impl ConfigTabState {
             .opened(true, Condition::Once)
             .tree_push_on_open(true)
             .build(ui, markers_window_closure);
+        #[cfg(feature = "api")]
+        let _api = TreeNode::new(&fl!("state-api"))
+            .flags(TreeNodeFlags::FRAMED)
+            .opened(true, Condition::Once)
+            .tree_push_on_open(true)
+            .build(ui, || self.draw_api(ui));
+    }
+
+    #[cfg(feature = "api")]
+    fn draw_api(&mut self, ui: &Ui) {
+        ui.dummy([4.0, 4.0]);
+        ui.text_wrapped(&fl!("state-api-description"));
+        ui.dummy([4.0, 4.0]);
+        let Some((mut enabled, port, allowed_origins)) = SETTINGS
+            .get()
+            .and_then(|settings| settings.try_read().ok())
+            .map(|settings| {
+                (
+                    settings.enable_api,
+                    settings.api_port,
+                    settings.api_allowed_origins.join(", "),
+                )
+            })
+        else {
+            return;
+        };
+        if ui.checkbox(&fl!("enable-state-api"), &mut enabled) {
+            Controller::try_send(ControllerEvent::ApiSettings { enabled, port });
+        }
+        let api_port = self.api_port.get_or_insert(port as i32);
+        if ui
+            .input_int(&fl!("state-api-port"), api_port)
+            .enter_returns_true(true)
+            .build()
+        {
+            let port = (*api_port).clamp(1, u16::MAX as i32) as u16;
+            Controller::try_send(ControllerEvent::ApiSettings { enabled, port });
+            self.api_port = None;
+        }
+        let api_allowed_origins = self.api_allowed_origins.get_or_insert(allowed_origins);
+        if ui
+            .input_text(&fl!("state-api-allowed-origins"), api_allowed_origins)
+            .enter_returns_true(true)
+            .build()
+        {
+            let allowed_origins = api_allowed_origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(str::to_string)
+                .collect();
+            Controller::try_send(ControllerEvent::ApiAllowedOrigins(allowed_origins));
+            self.api_allowed_origins = None;
+        }
+        ui.help_marker(|| {
+            ui.tooltip_text(fl!("state-api-allowed-origins-tooltip"));
+        });
+        if enabled {
+            ui.text_wrapped(fl!("state-api-address", port = port.to_string()));
+        }
     }
 }
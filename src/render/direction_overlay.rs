@@ -0,0 +1,98 @@
+use {
+    crate::{
+        fl,
+        timer::{PhaseState, TimerDirection},
+    },
+    glam::{Vec2, Vec3, Vec3Swizzles},
+    nexus::{
+        data_link::{
+            get_mumble_link_ptr,
+            mumble::{MumblePtr, UiState},
+            MumbleLink,
+        },
+        imgui::Ui,
+    },
+    std::f32::consts::TAU,
+    tokio::time::Instant,
+};
+
+const ARROW_SIZE: f32 = 24.0;
+const ARROW_SPACING: f32 = 72.0;
+
+pub struct DirectionOverlay;
+
+impl DirectionOverlay {
+    /**
+     * Draws an arrow near the top of the screen for each currently active direction,
+     * pointing from the player towards its destination relative to where the camera faces.
+     */
+    pub fn draw(ui: &Ui, phase_states: &[PhaseState]) {
+        let now = Instant::now();
+        let active: Vec<(&PhaseState, &TimerDirection)> = phase_states
+            .iter()
+            .flat_map(|ps| ps.directions.iter().map(move |d| (ps, d)))
+            .filter(|(ps, d)| d.is_active(ps.start, now))
+            .collect();
+        if active.is_empty() {
+            return;
+        }
+        let mumble_ptr = get_mumble_link_ptr() as *mut MumbleLink;
+        let Some(mumble) = (unsafe { MumblePtr::new(mumble_ptr) }) else {
+            return;
+        };
+        if mumble.read_ui_state().contains(UiState::IS_MAP_OPEN) {
+            return;
+        }
+        let player = Vec3::from_array(mumble.read_avatar().position);
+        let front = Vec3::from_array(mumble.read_camera().front).xz();
+
+        let [game_width, game_height] = ui.io().display_size;
+        let centre_x = game_width / 2.0;
+        let top_y = game_height * 0.12;
+        let offset = (active.len() - 1) as f32 * ARROW_SPACING / 2.0;
+
+        let draw_list = ui.get_foreground_draw_list();
+        for (i, (ps, direction)) in active.into_iter().enumerate() {
+            let centre = Vec2::new(centre_x - offset + i as f32 * ARROW_SPACING, top_y);
+            let to_destination = (direction.destination - player).xz();
+            // GW2 maps are left-handed: x is east, z is north.
+            let heading = to_destination.x.atan2(to_destination.y) - front.x.atan2(front.y);
+            let forward = Vec2::new(heading.sin(), -heading.cos());
+            let side = forward.perp();
+            let tip = centre + forward * ARROW_SIZE;
+            let back = centre - forward * (ARROW_SIZE * 0.5);
+            let left = back + side * (ARROW_SIZE * 0.6);
+            let right = back - side * (ARROW_SIZE * 0.6);
+
+            let pulse = (direction.scroll(ps.start, now) * TAU).sin() * 0.5 + 0.5;
+            let colour = [1.0, 0.85, 0.2, 0.6 + 0.4 * pulse];
+            draw_list
+                .add_triangle(tip.to_array(), left.to_array(), right.to_array(), colour)
+                .filled(true)
+                .build();
+            draw_list
+                .add_triangle(
+                    tip.to_array(),
+                    left.to_array(),
+                    right.to_array(),
+                    [0.0, 0.0, 0.0, 1.0],
+                )
+                .thickness(2.0)
+                .build();
+
+            let text = fl!(
+                "direction-distance",
+                name = direction.name.clone(),
+                distance = format!("{:.0}", to_destination.length())
+            );
+            let text_size = Vec2::from(ui.calc_text_size(&text));
+            let text_pos = centre + Vec2::new(-text_size.x / 2.0, ARROW_SIZE + 4.0);
+            draw_list.add_text(
+                (text_pos + Vec2::ONE).to_array(),
+                [0.0, 0.0, 0.0, 1.0],
+                &text,
+            );
+            draw_list.add_text(text_pos.to_array(), [1.0, 1.0, 1.0, 1.0], &text);
+        }
+    }
+}
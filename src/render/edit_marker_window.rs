@@ -4,7 +4,10 @@ use {
         fl,
         marker::{
             atomic::MarkerInputData,
-            format::{MarkerEntry, MarkerFiletype, MarkerSet, MarkerType},
+            format::{
+                AutoplaceTrigger, MarkerEntry, MarkerFileInfo, MarkerFiletype, MarkerSet,
+                MarkerType,
+            },
         },
         util::{ComboInput, PositionInput, UiExt},
         ControllerEvent, Controller, ACCOUNT_NAME_CELL,
@@ -19,6 +22,7 @@ use {
     },
     std::{f32, mem, path::PathBuf},
     strum::IntoEnumIterator,
+    uuid::Uuid,
 };
 
 /*
@@ -29,12 +33,17 @@ use {
 */
 pub struct EditMarkerWindowState {
     pub open: bool,
+    pub uuid: Option<Uuid>,
     pub formatted_name: String,
     pub name: String,
     pub description: String,
     pub author: String,
     pub category: ComboInput,
     pub trigger: PositionInput,
+    /// Kept as it was loaded, as it can only be changed in the file for now.
+    pub autoplace: Option<AutoplaceTrigger>,
+    /// Kept as it was loaded, for converting the file back to another format.
+    pub file_info: Option<MarkerFileInfo>,
     pub map_id: i32,
     pub markers: [IndividualMarkerState; 8],
     pub path: Option<String>,
@@ -105,9 +114,12 @@ impl EditMarkerWindowState {
     pub fn new() -> Self {
         Self {
             open: false,
+            uuid: Default::default(),
             formatted_name: Default::default(),
             name: Default::default(),
             trigger: Default::default(),
+            autoplace: Default::default(),
+            file_info: Default::default(),
             category: ComboInput::new(&fl!("category")),
             description: Default::default(),
             map_id: Default::default(),
@@ -216,10 +228,13 @@ impl EditMarkerWindowState {
             .flat_map(|(i, k)| self.markers[i].to_marker_entry(k))
             .collect();
         Some(MarkerSet {
+            uuid: self.uuid,
             enabled,
             category: self.category.result(),
             markers,
             trigger: self.trigger.position?.into(),
+            autoplace: self.autoplace.clone(),
+            file_info: self.file_info.clone(),
             name: self.name.clone(),
             author: Some(self.author.clone()),
             map_id: self.map_id as u32, // thanks imgui types o.o
@@ -240,11 +255,14 @@ impl EditMarkerWindowState {
         if !self.open {
             let trigger_position: Vec3 = ms.trigger.into();
             self.category.update(prev.category.data);
+            self.uuid = ms.uuid;
             self.markers = markers;
             self.original_category = ms.category.clone();
             self.category.entry = ms.category;
             self.name = ms.name;
             self.trigger.position = Some(trigger_position);
+            self.autoplace = ms.autoplace;
+            self.file_info = ms.file_info;
             self.description = ms.description;
             self.author = ms.author.unwrap_or("".to_string());
             self.map_id = ms.map_id as i32;
@@ -277,6 +295,7 @@ impl EditMarkerWindowState {
                 Default::default()
             };
             self.category.update(prev.category.data);
+            self.uuid = Some(Uuid::new_v4());
             self.author = author;
             self.map_id = map_id;
             self.request_filenames();
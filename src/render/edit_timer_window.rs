@@ -0,0 +1,707 @@
+use {
+    crate::{
+        fl,
+        marker::atomic::MarkerInputData,
+        timer::{
+            BlishAlert, BlishMarker, BlishPosition, BlishVec3, MapIds, Position,
+            TextAlertPriority, TimerFile, TimerPhase, TimerTrigger, TimerTriggerType,
+        },
+        util::PositionInput,
+        ControllerEvent, Controller, ACCOUNT_NAME_CELL,
+    },
+    glam::Vec2,
+    nexus::imgui::{ComboBox, Id, PopupModal, Selectable, TreeNode, TreeNodeFlags, Ui, Window},
+    relative_path::RelativePathBuf,
+    std::{path::PathBuf, sync::Arc},
+    strum::IntoEnumIterator,
+};
+
+/**
+ * A trigger being edited. Only the commonly authored parts of the trigger are exposed;
+ * everything else is carried over from the trigger it was opened with.
+ */
+pub struct TriggerInput {
+    pub trigger: TimerTrigger,
+    pub position: PositionInput,
+    pub antipode: PositionInput,
+    // a 2d position can't be captured from the player, so it's kept aside untouched
+    position_2d: Option<Vec2>,
+    antipode_2d: Option<Vec2>,
+    pub triggers: Vec<TriggerInput>,
+}
+
+impl TriggerInput {
+    fn position_input(position: Option<BlishPosition>) -> (PositionInput, Option<Vec2>) {
+        let mut input = PositionInput::default();
+        match position {
+            Some(Position::Vec3(vec3)) => {
+                input.position = Some(vec3.to_vec3());
+                (input, None)
+            }
+            Some(Position::Vec2(vec2)) => (input, Some(vec2)),
+            None => (input, None),
+        }
+    }
+
+    fn blish_position(input: &PositionInput, position_2d: Option<Vec2>) -> Option<BlishPosition> {
+        input
+            .position
+            .map(|position| Position::Vec3(BlishVec3::from_vec3(position)))
+            .or(position_2d.map(Position::Vec2))
+    }
+
+    pub fn from_trigger(trigger: TimerTrigger) -> Self {
+        let (position, position_2d) = Self::position_input(trigger.position);
+        let (antipode, antipode_2d) = Self::position_input(trigger.antipode);
+        let triggers = trigger
+            .triggers
+            .iter()
+            .cloned()
+            .map(Self::from_trigger)
+            .collect();
+        Self {
+            trigger,
+            position,
+            antipode,
+            position_2d,
+            antipode_2d,
+            triggers,
+        }
+    }
+
+    pub fn to_trigger(&self) -> Result<TimerTrigger, String> {
+        let mut trigger = self.trigger.clone();
+        if trigger.kind == TimerTriggerType::Key {
+            if trigger.key_flag().is_none() {
+                let key_bind = trigger.key_bind.clone().unwrap_or_default();
+                return Err(fl!("key-bind-invalid", key = key_bind));
+            }
+        }
+        trigger.position = Self::blish_position(&self.position, self.position_2d);
+        trigger.antipode = Self::blish_position(&self.antipode, self.antipode_2d);
+        trigger.triggers = self
+            .triggers
+            .iter()
+            .map(Self::to_trigger)
+            .collect::<Result<_, _>>()?;
+        Ok(trigger)
+    }
+
+    fn optional_float(ui: &Ui, label: &str, value: &mut Option<f32>) {
+        let mut input = value.unwrap_or_default();
+        if ui.input_float(label, &mut input).build() {
+            *value = Some(input);
+        }
+        if value.is_some() {
+            ui.same_line();
+            if ui.small_button(&fl!("clear")) {
+                *value = None;
+            }
+        }
+    }
+
+    fn optional_int(ui: &Ui, label: &str, value: &mut Option<u32>) {
+        let mut input = value.unwrap_or_default() as i32;
+        if ui.input_int(label, &mut input).build() {
+            *value = Some(input.max(0) as u32);
+        }
+        if value.is_some() {
+            ui.same_line();
+            if ui.small_button(&fl!("clear")) {
+                *value = None;
+            }
+        }
+    }
+
+    pub fn draw(&mut self, ui: &Ui) {
+        let kind_closure = || {
+            let mut selected = None;
+            for kind in TimerTriggerType::iter() {
+                if Selectable::new(kind.to_string())
+                    .selected(kind == self.trigger.kind)
+                    .build(ui)
+                {
+                    selected = Some(kind);
+                }
+            }
+            selected
+        };
+        if let Some(Some(kind)) = ComboBox::new(&fl!("trigger-type"))
+            .preview_value(self.trigger.kind.to_string())
+            .build(ui, kind_closure)
+        {
+            self.trigger.kind = kind;
+        }
+        use TimerTriggerType::*;
+        match self.trigger.kind {
+            All | Any | Not => {
+                for (i, trigger) in self.triggers.iter_mut().enumerate() {
+                    let pushy = ui.push_id(Id::Int(i as i32));
+                    ui.separator();
+                    trigger.draw(ui);
+                    pushy.pop();
+                }
+                if ui.button(&fl!("add-trigger")) {
+                    self.triggers.push(Self::from_trigger(Default::default()));
+                }
+                if !self.triggers.is_empty() {
+                    ui.same_line();
+                    if ui.button(&fl!("remove-trigger")) {
+                        self.triggers.pop();
+                    }
+                }
+                return;
+            }
+            Key => {
+                let key_bind = self.trigger.key_bind.get_or_insert_default();
+                ui.input_text(&fl!("key-bind"), key_bind).build();
+            }
+            Elapsed => Self::optional_float(ui, &fl!("elapsed"), &mut self.trigger.elapsed),
+            SkillCast | BuffApply | BuffRemove => {
+                Self::optional_int(ui, &fl!("skill-id"), &mut self.trigger.skill_id);
+                Self::optional_int(ui, &fl!("species-id"), &mut self.trigger.species_id);
+                ui.checkbox(&fl!("on-self"), &mut self.trigger.on_self);
+            }
+            AgentSpawn | AgentDeath | Breakbar => {
+                Self::optional_int(ui, &fl!("species-id"), &mut self.trigger.species_id);
+                ui.checkbox(&fl!("on-self"), &mut self.trigger.on_self);
+            }
+            HealthThreshold => {
+                Self::optional_float(ui, &fl!("threshold"), &mut self.trigger.threshold);
+                Self::optional_int(ui, &fl!("species-id"), &mut self.trigger.species_id);
+                ui.checkbox(&fl!("on-self"), &mut self.trigger.on_self);
+            }
+            Location => (),
+        }
+        ui.text(fl!("position"));
+        self.position.draw_display(ui, false);
+        self.position.draw_take_current(ui);
+        ui.same_line();
+        self.position.draw_edit_manual(ui, true);
+        Self::optional_float(ui, &fl!("radius"), &mut self.trigger.radius);
+        if self.trigger.radius.is_none() {
+            let antipode_pushy = ui.push_id("antipode");
+            ui.text(fl!("antipode"));
+            self.antipode.draw_display(ui, false);
+            self.antipode.draw_take_current(ui);
+            ui.same_line();
+            self.antipode.draw_edit_manual(ui, true);
+            antipode_pushy.pop();
+        }
+        ui.checkbox(&fl!("require-entry"), &mut self.trigger.require_entry);
+        ui.same_line();
+        ui.checkbox(&fl!("require-departure"), &mut self.trigger.require_departure);
+        ui.checkbox(&fl!("require-combat"), &mut self.trigger.require_combat);
+        ui.same_line();
+        ui.checkbox(
+            &fl!("require-out-of-combat"),
+            &mut self.trigger.require_out_of_combat,
+        );
+    }
+}
+
+/// Timestamps are edited as a comma separated list of seconds.
+fn timestamps_text(timestamps: &[f32]) -> String {
+    let timestamps: Vec<_> = timestamps.iter().map(f32::to_string).collect();
+    timestamps.join(", ")
+}
+
+fn parse_timestamps(text: &str) -> Result<Vec<f32>, String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|timestamp| !timestamp.is_empty())
+        .map(|timestamp| {
+            timestamp
+                .parse()
+                .map_err(|_| fl!("timestamp-invalid", timestamp = timestamp))
+        })
+        .collect()
+}
+
+pub struct AlertInput {
+    pub alert: BlishAlert,
+    pub timestamps: String,
+}
+
+impl AlertInput {
+    fn new(alert: BlishAlert) -> Self {
+        Self {
+            timestamps: timestamps_text(&alert.timestamps),
+            alert,
+        }
+    }
+
+    fn to_alert(&self) -> Result<BlishAlert, String> {
+        let mut alert = self.alert.clone();
+        alert.timestamps = parse_timestamps(&self.timestamps)?;
+        // an empty text or duration means there's no alert or warning at all
+        if alert.warning.as_ref().is_none_or(String::is_empty)
+            || alert.warning_duration.is_none_or(|duration| duration <= 0.0)
+        {
+            alert.warning = None;
+            alert.warning_duration = None;
+        }
+        if alert.alert.as_ref().is_none_or(String::is_empty)
+            || alert.alert_duration.is_none_or(|duration| duration <= 0.0)
+        {
+            alert.alert = None;
+            alert.alert_duration = None;
+        }
+        Ok(alert)
+    }
+
+    fn draw(&mut self, ui: &Ui) {
+        ui.input_text(&fl!("timestamps"), &mut self.timestamps).build();
+        let warning = self.alert.warning.get_or_insert_default();
+        ui.input_text(&fl!("warning"), warning).build();
+        let warning_duration = self.alert.warning_duration.get_or_insert(0.0);
+        ui.input_float(&fl!("warning-duration"), warning_duration).build();
+        let alert = self.alert.alert.get_or_insert_default();
+        ui.input_text(&fl!("alert"), alert).build();
+        let alert_duration = self.alert.alert_duration.get_or_insert(0.0);
+        ui.input_float(&fl!("alert-duration"), alert_duration).build();
+        let mut icon = self
+            .alert
+            .icon
+            .as_ref()
+            .map(|icon| icon.to_string())
+            .unwrap_or_default();
+        if ui.input_text(&fl!("icon"), &mut icon).build() {
+            self.alert.icon = (!icon.is_empty()).then(|| RelativePathBuf::from(icon.as_str()));
+        }
+    }
+}
+
+pub struct MarkerInput {
+    pub marker: BlishMarker,
+    pub position: PositionInput,
+    pub texture: String,
+    pub timestamps: String,
+}
+
+impl MarkerInput {
+    fn new(marker: BlishMarker) -> Self {
+        let mut position = PositionInput::default();
+        position.position = Some(marker.position.to_vec3());
+        Self {
+            position,
+            texture: marker.texture.to_string_lossy().to_string(),
+            timestamps: timestamps_text(&marker.timestamps),
+            marker,
+        }
+    }
+
+    fn to_marker(&self) -> Result<BlishMarker, String> {
+        let mut marker = self.marker.clone();
+        let position = self.position.position.ok_or_else(|| fl!("marker-no-position"))?;
+        marker.position = BlishVec3::from_vec3(position);
+        marker.texture = PathBuf::from(&self.texture);
+        marker.timestamps = parse_timestamps(&self.timestamps)?;
+        Ok(marker)
+    }
+
+    fn draw(&mut self, ui: &Ui) {
+        ui.input_text(&fl!("timestamps"), &mut self.timestamps).build();
+        ui.input_text(&fl!("texture"), &mut self.texture).build();
+        ui.input_float(&fl!("duration"), &mut self.marker.duration).build();
+        ui.input_float(&fl!("size"), &mut self.marker.size).build();
+        self.position.draw_display(ui, false);
+        self.position.draw_take_current(ui);
+        ui.same_line();
+        self.position.draw_edit_manual(ui, true);
+    }
+}
+
+pub struct PhaseInput {
+    pub phase: TimerPhase,
+    pub start: TriggerInput,
+    pub finish: Option<TriggerInput>,
+    pub alerts: Vec<AlertInput>,
+    pub markers: Vec<MarkerInput>,
+}
+
+impl PhaseInput {
+    fn new(phase: TimerPhase) -> Self {
+        Self {
+            start: TriggerInput::from_trigger(phase.start.clone()),
+            finish: phase.finish.clone().map(TriggerInput::from_trigger),
+            alerts: phase.alerts.iter().cloned().map(AlertInput::new).collect(),
+            markers: phase.markers.0.iter().cloned().map(MarkerInput::new).collect(),
+            phase,
+        }
+    }
+
+    fn empty(name: String) -> Self {
+        Self::new(TimerPhase {
+            name,
+            start: Default::default(),
+            finish: Default::default(),
+            alerts: Default::default(),
+            actions: Default::default(),
+            directions: Default::default(),
+            markers: Default::default(),
+            sounds: Default::default(),
+        })
+    }
+
+    fn to_phase(&self) -> Result<TimerPhase, String> {
+        let mut phase = self.phase.clone();
+        phase.start = self.start.to_trigger()?;
+        phase.finish = self
+            .finish
+            .as_ref()
+            .map(TriggerInput::to_trigger)
+            .transpose()?;
+        phase.alerts = self
+            .alerts
+            .iter()
+            .map(AlertInput::to_alert)
+            .collect::<Result<_, _>>()?;
+        phase.markers.0 = self
+            .markers
+            .iter()
+            .map(MarkerInput::to_marker)
+            .collect::<Result<_, _>>()?;
+        Ok(phase)
+    }
+
+    fn draw(&mut self, ui: &Ui) {
+        ui.input_text(&fl!("name"), &mut self.phase.name).build();
+        let start_closure = || self.start.draw(ui);
+        TreeNode::new(&fl!("phase-start"))
+            .tree_push_on_open(true)
+            .build(ui, start_closure);
+        let mut has_finish = self.finish.is_some();
+        if ui.checkbox(&fl!("phase-has-finish"), &mut has_finish) {
+            self.finish = has_finish.then(|| TriggerInput::from_trigger(Default::default()));
+        }
+        if let Some(finish) = &mut self.finish {
+            let finish_closure = || finish.draw(ui);
+            TreeNode::new(&fl!("phase-finish"))
+                .tree_push_on_open(true)
+                .build(ui, finish_closure);
+        }
+        let alerts_closure = || {
+            let mut removed = None;
+            for (i, alert) in self.alerts.iter_mut().enumerate() {
+                let pushy = ui.push_id(Id::Int(i as i32));
+                ui.separator();
+                alert.draw(ui);
+                if ui.button(&fl!("remove-alert")) {
+                    removed = Some(i);
+                }
+                pushy.pop();
+            }
+            if let Some(i) = removed {
+                self.alerts.remove(i);
+            }
+            if ui.button(&fl!("add-alert")) {
+                self.alerts.push(AlertInput::new(Default::default()));
+            }
+        };
+        TreeNode::new(&fl!("alerts-arg", count = self.alerts.len()))
+            .tree_push_on_open(true)
+            .build(ui, alerts_closure);
+        let markers_closure = || {
+            let mut removed = None;
+            for (i, marker) in self.markers.iter_mut().enumerate() {
+                let pushy = ui.push_id(Id::Int(i as i32));
+                ui.separator();
+                marker.draw(ui);
+                if ui.button(&fl!("remove-marker")) {
+                    removed = Some(i);
+                }
+                pushy.pop();
+            }
+            if let Some(i) = removed {
+                self.markers.remove(i);
+            }
+            if ui.button(&fl!("add-marker")) {
+                let mut marker = MarkerInput::new(Default::default());
+                if let Some(mid) = MarkerInputData::read() {
+                    marker.position.position = Some(mid.local_player_pos);
+                }
+                self.markers.push(marker);
+            }
+        };
+        TreeNode::new(&fl!("markers-arg", count = self.markers.len()))
+            .tree_push_on_open(true)
+            .build(ui, markers_closure);
+    }
+}
+
+pub struct EditTimerWindowState {
+    pub open: bool,
+    pub formatted_name: String,
+    pub timer: Option<TimerFile>,
+    pub icon: String,
+    pub map_ids: String,
+    pub reset: TriggerInput,
+    pub phases: Vec<PhaseInput>,
+    pub filename: String,
+    pub problems: Vec<String>,
+}
+
+impl EditTimerWindowState {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            formatted_name: Default::default(),
+            timer: Default::default(),
+            icon: Default::default(),
+            map_ids: Default::default(),
+            reset: TriggerInput::from_trigger(Default::default()),
+            phases: Default::default(),
+            filename: Default::default(),
+            problems: Default::default(),
+        }
+    }
+
+    fn parse_map_ids(&self) -> Result<MapIds, String> {
+        let map_ids = self
+            .map_ids
+            .split(',')
+            .map(str::trim)
+            .filter(|map_id| !map_id.is_empty())
+            .map(|map_id| {
+                map_id
+                    .parse()
+                    .map_err(|_| fl!("map-id-invalid", id = map_id))
+            })
+            .collect::<Result<Vec<u32>, _>>()?;
+        match map_ids.as_slice() {
+            [] => Err(fl!("map-id-wrong")),
+            [map_id] => Ok(MapIds::One(*map_id)),
+            _ => Ok(MapIds::Many(map_ids)),
+        }
+    }
+
+    pub fn to_timer_file(&self) -> Result<TimerFile, Vec<String>> {
+        let mut problems = Vec::new();
+        let Some(timer) = &self.timer else {
+            return Err(problems);
+        };
+        if timer.id.is_empty() {
+            problems.push(fl!("id-empty"));
+        }
+        if timer.name.is_empty() {
+            problems.push(fl!("name-empty"));
+        }
+        if self.filename.is_empty() {
+            problems.push(fl!("filename-empty"));
+        } else if self.filename.contains(['/', '\\']) || self.filename.contains("..") {
+            problems.push(fl!("filename-invalid"));
+        }
+        if self.phases.is_empty() {
+            problems.push(fl!("no-phases"));
+        }
+        let map_ids = self.parse_map_ids().map_err(|p| problems.push(p)).ok();
+        let reset = self.reset.to_trigger().map_err(|p| problems.push(p)).ok();
+        let phases: Vec<_> = self
+            .phases
+            .iter()
+            .map(PhaseInput::to_phase)
+            .filter_map(|phase| phase.map_err(|p| problems.push(p)).ok())
+            .collect();
+        let (Some(map_ids), Some(reset)) = (map_ids, reset) else {
+            return Err(problems);
+        };
+        if !problems.is_empty() {
+            return Err(problems);
+        }
+        Ok(TimerFile {
+            association: None,
+            path: None,
+            map_ids,
+            icon: RelativePathBuf::from(self.icon.as_str()),
+            reset,
+            phases,
+            ..timer.clone()
+        })
+    }
+
+    pub fn open_edit(&mut self, timer: Arc<TimerFile>) {
+        *self = Self::new();
+        let mut timer = (*timer).clone();
+        // pack timers are overwritten on update, so edits to them are saved as ad-hoc copies,
+        // under their own id so that the two can't be mistaken for one another
+        if timer.association.is_some() {
+            timer.id = format!("{}-edited", timer.id);
+        }
+        self.filename = match (&timer.association, &timer.path) {
+            (None, Some(path)) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            _ => format!("{}.bhtimer", timer.id),
+        };
+        self.icon = timer.icon.to_string();
+        self.map_ids = timer.map_ids.to_string();
+        self.reset = TriggerInput::from_trigger(timer.reset.clone());
+        self.phases = timer.phases.drain(..).map(PhaseInput::new).collect();
+        self.timer = Some(timer);
+        self.open = true;
+    }
+
+    pub fn open(&mut self) {
+        *self = Self::new();
+        let author = ACCOUNT_NAME_CELL
+            .get()
+            .map(|account| account.trim_start_matches(':').to_string())
+            .unwrap_or_default();
+        if let Some(mid) = MarkerInputData::read() {
+            self.map_ids = mid.map_id.to_string();
+        }
+        self.timer = Some(TimerFile {
+            association: None,
+            path: None,
+            id: Default::default(),
+            name: Default::default(),
+            category: Default::default(),
+            description: Default::default(),
+            author,
+            icon: Default::default(),
+            map_ids: MapIds::Many(Default::default()),
+            reset: Default::default(),
+            phases: Default::default(),
+            priority: Default::default(),
+        });
+        self.phases.push(PhaseInput::empty(fl!("phase-default-name", index = 1)));
+        self.open = true;
+    }
+
+    fn draw_validate(&self, ui: &Ui) {
+        if !self.problems.is_empty() {
+            ui.text_wrapped(fl!("validation-fail"));
+        }
+        for problem in &self.problems {
+            ui.bullet();
+            ui.text_colored([1.0, 0.0, 0.0, 1.0], problem);
+        }
+    }
+
+    fn save_file(&mut self) -> bool {
+        match self.to_timer_file() {
+            Ok(timer) => {
+                let mut filename = self.filename.clone();
+                if !filename.ends_with(".bhtimer") {
+                    filename.push_str(".bhtimer");
+                }
+                Controller::try_send(ControllerEvent::SaveTimer(timer, filename.into()));
+                true
+            }
+            Err(problems) => {
+                self.problems = problems;
+                false
+            }
+        }
+    }
+
+    fn draw_details(&mut self, ui: &Ui) {
+        let Some(timer) = &mut self.timer else {
+            return;
+        };
+        ui.input_text(&fl!("id"), &mut timer.id).build();
+        ui.input_text(&fl!("name"), &mut timer.name).build();
+        ui.input_text(&fl!("category"), &mut timer.category).build();
+        ui.input_text(&fl!("author"), &mut timer.author).build();
+        ui.input_text(&fl!("icon"), &mut self.icon).build();
+        ui.input_text(&fl!("map-ids"), &mut self.map_ids).build();
+        ui.same_line();
+        if ui.button(&fl!("set-map-id")) {
+            if let Some(mid) = MarkerInputData::read() {
+                self.map_ids = mid.map_id.to_string();
+            }
+        }
+        let priority_closure = || {
+            let mut selected = None;
+            for priority in TextAlertPriority::iter() {
+                if Selectable::new(priority.to_string())
+                    .selected(priority == timer.priority)
+                    .build(ui)
+                {
+                    selected = Some(priority);
+                }
+            }
+            selected
+        };
+        if let Some(Some(priority)) = ComboBox::new(&fl!("text-alert-priority"))
+            .preview_value(timer.priority.to_string())
+            .build(ui, priority_closure)
+        {
+            timer.priority = priority;
+        }
+        ui.input_text_multiline(&fl!("description"), &mut timer.description, [0.0, 0.0])
+            .build();
+    }
+
+    pub fn draw(&mut self, ui: &Ui) {
+        let mut open = self.open;
+        if !open {
+            return;
+        }
+        let closed = Window::new(&fl!("edit-timer"))
+            .size([400.0, 500.0], nexus::imgui::Condition::FirstUseEver)
+            .opened(&mut open)
+            .build(ui, || {
+                self.draw_details(ui);
+                ui.dummy([4.0; 2]);
+                let reset_closure = || self.reset.draw(ui);
+                TreeNode::new(&fl!("timer-reset"))
+                    .flags(TreeNodeFlags::FRAMED)
+                    .tree_push_on_open(true)
+                    .build(ui, reset_closure);
+                let mut removed = None;
+                for (i, phase) in self.phases.iter_mut().enumerate() {
+                    let pushy = ui.push_id(Id::Int(i as i32));
+                    let phase_closure = || {
+                        phase.draw(ui);
+                        if ui.button(&fl!("remove-phase")) {
+                            removed = Some(i);
+                        }
+                    };
+                    TreeNode::new(&fl!("phase-arg", name = phase.phase.name.clone()))
+                        .flags(TreeNodeFlags::FRAMED)
+                        .tree_push_on_open(true)
+                        .build(ui, phase_closure);
+                    pushy.pop();
+                }
+                if let Some(i) = removed {
+                    self.phases.remove(i);
+                }
+                if ui.button(&fl!("add-phase")) {
+                    let name = fl!("phase-default-name", index = self.phases.len() + 1);
+                    self.phases.push(PhaseInput::empty(name));
+                }
+                ui.dummy([4.0; 2]);
+                ui.input_text(&fl!("filename"), &mut self.filename).build();
+                self.draw_validate(ui);
+                if ui.button(&fl!("save")) {
+                    self.problems.clear();
+                    let name = self.timer.as_ref().map(|t| t.name.clone()).unwrap_or_default();
+                    self.formatted_name = fl!("save-item", item = name);
+                    ui.open_popup(&self.formatted_name);
+                }
+                let mut saved = false;
+                if let Some(_token) = PopupModal::new(&self.formatted_name)
+                    .always_auto_resize(true)
+                    .begin_popup(ui)
+                {
+                    ui.text_wrapped(fl!("save-timer-explanation", filename = self.filename.clone()));
+                    if ui.button(fl!("save")) {
+                        saved = self.save_file();
+                        ui.close_current_popup();
+                    }
+                    ui.same_line();
+                    if ui.button(fl!("cancel")) {
+                        ui.close_current_popup();
+                    }
+                }
+                saved
+            });
+        self.open = match closed {
+            Some(true) => false,
+            _ => open,
+        };
+    }
+}
@@ -4,6 +4,7 @@ use {
         controller::ControllerEvent,
         fl,
         marker::{
+            agent::{AgentPreset, AgentPresetFile, AgentSelector, AgentStep},
             atomic::{LocalPoint, MarkerInputData, ScreenPoint, SignObtainer},
             format::MarkerSet,
         },
@@ -15,8 +16,8 @@ use {
     indexmap::IndexMap,
     nexus::{
         imgui::{
-            ChildWindow, Condition, PopupModal, Selectable, TableColumnSetup, TableFlags, TreeNode,
-            TreeNodeFlags, Ui, WindowFlags,
+            ChildWindow, ComboBox, Condition, PopupModal, Selectable, TableColumnSetup, TableFlags,
+            TreeNode, TreeNodeFlags, Ui, WindowFlags,
         },
         paths::get_addon_dir,
     },
@@ -26,11 +27,23 @@ use {
     },
 };
 
+#[cfg(feature = "markers-edit")]
+use {
+    crate::{marker::format::MarkerFiletype, util::UiExt},
+    strum::IntoEnumIterator,
+};
+
 pub struct MarkerTabState {
     markers: IndexMap<String, Vec<Arc<MarkerSet>>>,
     pub marker_selection: Option<Arc<MarkerSet>>,
     category_status: HashSet<String>,
     formatted_name: String,
+    agent_presets: Vec<Arc<AgentPreset>>,
+    agent_preset_selection: Option<Arc<AgentPreset>>,
+    /// The steps of the preset being applied, as the controller last told us, and which is next.
+    pub agent_steps: (Vec<AgentStep>, usize),
+    /// The account of the current target, if arcdps knows it.
+    pub agent_target: Option<String>,
 }
 
 impl MarkerTabState {
@@ -42,6 +55,10 @@ impl MarkerTabState {
             marker_selection: Default::default(),
             category_status: Default::default(),
             formatted_name: Default::default(),
+            agent_presets: Default::default(),
+            agent_preset_selection: Default::default(),
+            agent_steps: Default::default(),
+            agent_target: Default::default(),
         }
     }
 
@@ -49,7 +66,7 @@ impl MarkerTabState {
         ui.columns(2, "marker_tab_start", true);
         self.draw_sidebar(ui, state_errors);
         ui.next_column();
-        self.draw_main(ui);
+        self.draw_main(ui, state_errors);
         ui.columns(1, "marker_tab_end", false)
     }
 
@@ -186,7 +203,7 @@ impl MarkerTabState {
         selected
     }
 
-    fn draw_main(&mut self, ui: &Ui) {
+    fn draw_main(&mut self, ui: &Ui, state_errors: &mut HashMap<String, anyhow::Error>) {
         let child_window_flags = WindowFlags::HORIZONTAL_SCROLLBAR;
         ChildWindow::new("timer_main")
             .flags(child_window_flags)
@@ -218,6 +235,12 @@ impl MarkerTabState {
                     ui.separator();
                     ui.dummy([4.0; 2]);
                 }
+                TreeNode::new(&fl!("agent-presets"))
+                    .flags(TreeNodeFlags::FRAMED)
+                    .opened(false, Condition::Once)
+                    .tree_push_on_open(false)
+                    .build(ui, || self.draw_agent_presets(ui, state_errors));
+                ui.dummy([4.0; 2]);
                 if let Some(selected_marker_set) = &self.marker_selection {
                     let pushy = ui.push_id(&selected_marker_set.name);
                     RenderState::font_text("big", ui, &selected_marker_set.name);
@@ -268,6 +291,30 @@ impl MarkerTabState {
                             ui.close_current_popup();
                         }
                     }
+                    #[cfg(feature = "markers-edit")]
+                    if let Some(path) = &selected_marker_set.path {
+                        let filetype_closure = || {
+                            let mut selected = None;
+                            for item in MarkerFiletype::iter() {
+                                if Selectable::new(item.to_string()).build(ui) {
+                                    selected = Some(item);
+                                }
+                            }
+                            selected
+                        };
+                        if let Some(Some(filetype)) = ComboBox::new(fl!("marker-file-convert"))
+                            .preview_value("")
+                            .build(ui, filetype_closure)
+                        {
+                            Controller::try_send(ControllerEvent::ConvertMarkerFile(
+                                path.clone(),
+                                filetype,
+                            ));
+                        }
+                        ui.help_marker(|| {
+                            ui.tooltip_text(fl!("marker-file-convert-explanation"));
+                        });
+                    }
                     let screen_positions: Vec<ScreenPoint> = selected_marker_set
                         .markers
                         .iter()
@@ -362,6 +409,141 @@ impl MarkerTabState {
                 }
             });
     }
+    fn draw_agent_presets(&mut self, ui: &Ui, state_errors: &mut HashMap<String, anyhow::Error>) {
+        ui.text_wrapped(&fl!("agent-presets-description"));
+        let addon_dir = get_addon_dir("Taimi").expect("Invalid addon dir");
+        let presets_path = addon_dir.join("markers").join(AgentPresetFile::FILENAME);
+        RenderState::draw_open_button(
+            state_errors,
+            ui,
+            fl!("open-button", kind = AgentPresetFile::FILENAME),
+            presets_path.to_string_lossy().to_string(),
+        );
+        ui.same_line();
+        if ui.button(fl!("clear-agent-markers")) {
+            Controller::try_send(ControllerEvent::ClearAgentMarkers);
+        }
+        if self.agent_presets.is_empty() {
+            ui.text_wrapped(&fl!("no-agent-presets", file = AgentPresetFile::FILENAME));
+            return;
+        }
+
+        let preset_closure = || {
+            let mut selected = None;
+            for preset in &self.agent_presets {
+                let is_selected = self
+                    .agent_preset_selection
+                    .as_ref()
+                    .is_some_and(|selection| Arc::ptr_eq(selection, preset));
+                if Selectable::new(&preset.name)
+                    .selected(is_selected)
+                    .build(ui)
+                {
+                    selected = Some(preset.clone());
+                }
+            }
+            selected
+        };
+        let preview = match &self.agent_preset_selection {
+            Some(preset) => preset.name.clone(),
+            None => "".to_string(),
+        };
+        if let Some(Some(selection)) = ComboBox::new(fl!("agent-preset"))
+            .preview_value(preview)
+            .build(ui, preset_closure)
+        {
+            self.agent_preset_selection = Some(selection);
+        }
+        if let Some(preset) = &self.agent_preset_selection {
+            if !preset.description.is_empty() {
+                ui.text_wrapped(&preset.description);
+            }
+            if ui.button(fl!("agent-preset-start")) {
+                Controller::try_send(ControllerEvent::AgentPresetStart(preset.clone()));
+            }
+        }
+
+        let (steps, current) = &self.agent_steps;
+        if steps.is_empty() {
+            return;
+        }
+        ui.same_line();
+        if ui.button(fl!("agent-preset-stop")) {
+            Controller::try_send(ControllerEvent::AgentPresetStop);
+        }
+        match steps.get(*current).and_then(|step| step.member.as_ref()) {
+            Some(member) => {
+                ui.text_colored(
+                    [1.0, 0.85, 0.2, 1.0],
+                    fl!("agent-preset-target", member = member.display_name()),
+                );
+                match &self.agent_target {
+                    Some(target) if target.eq_ignore_ascii_case(&member.account_name) => {
+                        ui.text_wrapped(fl!("agent-preset-targeting", account = target.as_str()));
+                    }
+                    Some(target) => ui.text_colored(
+                        [1.0, 0.0, 0.0, 1.0],
+                        fl!("agent-preset-wrong-target", account = target.as_str()),
+                    ),
+                    None => ui.text_disabled(fl!("agent-preset-target-unknown")),
+                }
+                if ui.button(fl!("agent-preset-apply")) {
+                    Controller::try_send(ControllerEvent::AgentPresetStep(true));
+                }
+                ui.same_line();
+                if ui.button(fl!("agent-preset-skip")) {
+                    Controller::try_send(ControllerEvent::AgentPresetStep(false));
+                }
+            }
+            None => ui.text_wrapped(&fl!("agent-preset-finished")),
+        }
+
+        let table_flags = TableFlags::RESIZABLE | TableFlags::ROW_BG | TableFlags::BORDERS;
+        let table_token = ui.begin_table_header_with_flags(
+            "agent_preset_steps",
+            [
+                TableColumnSetup::new(&fl!("marker-type")),
+                TableColumnSetup::new(&fl!("assigned-by-header")),
+                TableColumnSetup::new(&fl!("member-header")),
+            ],
+            table_flags,
+        );
+        ui.table_next_column();
+        for (i, step) in steps.iter().enumerate() {
+            step.marker.icon(ui);
+            ui.table_next_column();
+            ui.text_wrapped(match &step.target {
+                AgentSelector::Account(account) => account.clone(),
+                AgentSelector::Role(role) => role.to_string(),
+                AgentSelector::Subgroup(subgroup) => fl!("subgroup-arg", subgroup = *subgroup),
+            });
+            ui.table_next_column();
+            match &step.member {
+                Some(member) if i < *current => ui.text_disabled(member.display_name()),
+                Some(member) if i == *current => {
+                    ui.text_colored([1.0, 0.85, 0.2, 1.0], member.display_name());
+                }
+                Some(member) => ui.text_wrapped(member.display_name()),
+                None => ui.text_colored([1.0, 0.0, 0.0, 1.0], fl!("agent-preset-unmatched")),
+            }
+            ui.table_next_column();
+        }
+        if let Some(token) = table_token {
+            token.end();
+        }
+    }
+
+    pub fn agent_presets_update(&mut self, presets: Vec<Arc<AgentPreset>>) {
+        let selection = self.agent_preset_selection.take();
+        self.agent_preset_selection = selection.and_then(|selection| {
+            presets
+                .iter()
+                .find(|preset| preset.name == selection.name)
+                .cloned()
+        });
+        self.agent_presets = presets;
+    }
+
     pub fn marker_update(&mut self, markers: HashMap<String, Vec<Arc<MarkerSet>>>) {
         self.markers.clear();
         for (category, markers) in markers {
@@ -1,22 +1,28 @@
 use {
     crate::{
         fl,
-        marker::{atomic::MarkerInputData, format::MarkerSet},
+        marker::{
+            atomic::MarkerInputData,
+            format::{MarkerSet, RearmPolicy},
+        },
         ControllerEvent, Controller, SETTINGS,
     },
     nexus::imgui::{Id, TableColumnFlags, TableColumnSetup, TableFlags, Ui, Window},
-    std::sync::Arc,
+    std::{collections::HashSet, sync::Arc},
 };
 
 pub struct MarkerWindowState {
     pub open: bool,
     pub markers_for_map: Vec<Arc<MarkerSet>>,
+    /// Those that have been placed automatically and are waiting to re-arm.
+    pub spent_markers: HashSet<Arc<MarkerSet>>,
 }
 
 impl MarkerWindowState {
     pub fn new() -> Self {
         Self {
             markers_for_map: Default::default(),
+            spent_markers: Default::default(),
             open: false,
         }
     }
@@ -25,6 +31,19 @@ impl MarkerWindowState {
         self.markers_for_map = markers;
     }
 
+    fn rearm_text(policy: &RearmPolicy) -> String {
+        match policy {
+            RearmPolicy::Never => fl!("rearm-never"),
+            RearmPolicy::OnLeave { hysteresis } => {
+                fl!("rearm-on-leave", distance = format!("{:.0}", hysteresis.0))
+            }
+            RearmPolicy::Cooldown { seconds } => {
+                fl!("rearm-cooldown", seconds = format!("{:.0}", seconds.0))
+            }
+            RearmPolicy::CombatExit => fl!("rearm-combat-exit"),
+        }
+    }
+
     pub fn draw(&mut self, ui: &Ui) {
         let mut open = self.open;
         if let Some(settings) = SETTINGS.get().and_then(|settings| settings.try_read().ok()) {
@@ -68,6 +87,12 @@ impl MarkerWindowState {
                                     init_width_or_weight: 0.0,
                                     user_id: Id::Str("description"),
                                 },
+                                TableColumnSetup {
+                                    name: &fl!("autoplace"),
+                                    flags: TableColumnFlags::WIDTH_STRETCH,
+                                    init_width_or_weight: 0.0,
+                                    user_id: Id::Str("autoplace"),
+                                },
                                 TableColumnSetup {
                                     name: &fl!("actions"),
                                     flags: TableColumnFlags::WIDTH_STRETCH,
@@ -93,9 +118,25 @@ impl MarkerWindowState {
                             ui.table_next_column();
                             ui.text_wrapped(format!("{}", marker.description));
                             ui.table_next_column();
+                            let spent = self.spent_markers.contains(marker);
+                            let (color, text) = match spent {
+                                true => ([1.0, 1.0, 0.0, 1.0], fl!("autoplace-spent")),
+                                false => ([0.0, 1.0, 0.0, 1.0], fl!("autoplace-armed")),
+                            };
+                            ui.text_colored(color, text);
+                            ui.text_disabled(Self::rearm_text(&marker.rearm_policy()));
+                            ui.table_next_column();
                             if ui.button(&fl!("markers-place")) {
                                 Controller::try_send(ControllerEvent::SetMarker(marker.clone()));
                             }
+                            if spent {
+                                ui.same_line();
+                                if ui.button(&fl!("autoplace-rearm")) {
+                                    Controller::try_send(ControllerEvent::RearmMarker(
+                                        marker.clone(),
+                                    ));
+                                }
+                            }
                             ui.table_next_column();
                             id_token.end();
                         }
@@ -1,7 +1,11 @@
+pub mod alert_queue;
 pub mod config_tab;
 pub mod data_source_tab;
+pub mod direction_overlay;
 #[cfg(feature = "markers-edit")]
 pub mod edit_marker_window;
+#[cfg(feature = "timers-edit")]
+pub mod edit_timer_window;
 pub mod info_tab;
 #[cfg(feature = "markers")]
 pub mod marker_tab;
@@ -15,8 +19,10 @@ pub mod marker_window;
 
 #[allow(unused_imports)]
 pub use {
+    alert_queue::{QueuedTextAlert, TextAlertQueue},
     config_tab::ConfigTabState,
     data_source_tab::DataSourceTabState,
+    direction_overlay::DirectionOverlay,
     info_tab::InfoTabState,
     primary_window::PrimaryWindowState,
     state::{Alignment, RenderEvent, RenderState, TextFont},
@@ -1,15 +1,22 @@
 #[cfg(feature = "markers")]
-use {crate::marker::atomic::MarkerInputData, crate::marker::format::MarkerSet};
+use {
+    crate::marker::agent::{AgentPreset, AgentStep},
+    crate::marker::atomic::MarkerInputData,
+    crate::marker::format::MarkerSet,
+};
 use {
     crate::{
         controller::ControllerEvent,
         fl,
         marker::format::MarkerType,
         marker_icon_data,
-        render::{MarkerWindowState, PrimaryWindowState, TimerWindowState},
+        render::{
+            DirectionOverlay, MarkerWindowState, PrimaryWindowState, TextAlertQueue,
+            TimerWindowState,
+        },
         settings::ProgressBarSettings,
-        timer::{PhaseState, TextAlert, TimerFile},
-        Controller, IMGUI_TEXTURES, RENDER_SENDER,
+        timer::{AlertPlayer, AttemptStats, PhaseState, QueuedAlert, TextAlert, TimerFile},
+        Controller, IMGUI_TEXTURES, RENDER_SENDER, REQUESTED_TEXTURES, SETTINGS,
     },
     glam::Vec2,
     nexus::{
@@ -23,7 +30,7 @@ use {
     serde::{Deserialize, Serialize},
     std::{
         cell::Cell,
-        collections::HashMap,
+        collections::{HashMap, HashSet},
         path::PathBuf,
         sync::{Arc, MutexGuard},
     },
@@ -34,23 +41,42 @@ use {
 #[cfg(feature = "markers-edit")]
 use super::edit_marker_window::EditMarkerWindowState;
 
+#[cfg(feature = "timers-edit")]
+use super::edit_timer_window::EditTimerWindowState;
+
 pub enum RenderEvent {
     TimerData(Vec<Arc<TimerFile>>),
+    AttemptStats(HashMap<String, AttemptStats>),
     #[cfg(feature = "markers")]
     MarkerData(HashMap<String, Vec<Arc<MarkerSet>>>),
     MarkerMap(Vec<Arc<MarkerSet>>),
+    #[cfg(feature = "markers")]
+    SpentMarkers(HashSet<Arc<MarkerSet>>),
+    #[cfg(feature = "markers")]
+    AgentPresets(Vec<Arc<AgentPreset>>),
+    #[cfg(feature = "markers")]
+    AgentSteps(Vec<AgentStep>, usize),
+    /// The account of the current target, as far as arcdps knows.
+    #[cfg(feature = "markers")]
+    AgentTarget(Option<String>),
     AlertFeed(PhaseState),
     OpenableError(String, anyhow::Error),
     AlertReset(Arc<TimerFile>),
     AlertStart(TextAlert),
     AlertEnd(Arc<TimerFile>),
+    AlertQueue(Arc<TimerFile>, Vec<QueuedAlert>),
+    AlertPlayer(AlertPlayer),
     CheckingForUpdates(bool),
+    TraceRecording(bool),
+    TraceReplaying(bool),
     #[allow(dead_code)]
     RenderKeybindUpdate,
     #[cfg(feature = "markers-edit")]
     OpenEditMarkers(Option<MarkerSet>),
     #[cfg(feature = "markers-edit")]
     GiveMarkerPaths(Vec<PathBuf>),
+    #[cfg(feature = "timers-edit")]
+    OpenEditTimer(Option<Arc<TimerFile>>),
     ProgressBarUpdate(ProgressBarSettings),
     Quit,
 }
@@ -70,11 +96,14 @@ pub struct RenderState {
     pub primary_window: PrimaryWindowState,
     #[cfg(feature = "markers-edit")]
     pub edit_marker_window: EditMarkerWindowState,
+    #[cfg(feature = "timers-edit")]
+    pub edit_timer_window: EditTimerWindowState,
     #[cfg(feature = "markers")]
     pub marker_window: MarkerWindowState,
     timer_window: TimerWindowState,
     receiver: Receiver<RenderEvent>,
-    alert: Option<TextAlert>,
+    alert_queue: TextAlertQueue,
+    max_text_alerts: usize,
     last_display_size: Option<[f32; 2]>,
     pub state_errors: HashMap<String, anyhow::Error>,
 }
@@ -83,11 +112,14 @@ impl RenderState {
     pub fn new(receiver: Receiver<RenderEvent>) -> Self {
         Self {
             receiver,
-            alert: Default::default(),
+            alert_queue: Default::default(),
+            max_text_alerts: 1,
             primary_window: PrimaryWindowState::new(),
             timer_window: TimerWindowState::new(),
             #[cfg(feature = "markers-edit")]
             edit_marker_window: EditMarkerWindowState::new(),
+            #[cfg(feature = "timers-edit")]
+            edit_timer_window: EditTimerWindowState::new(),
             #[cfg(feature = "markers")]
             marker_window: MarkerWindowState::new(),
             last_display_size: Default::default(),
@@ -121,10 +153,19 @@ impl RenderState {
                     MarkerMap(markers) => {
                         self.marker_window.new_map_markers(markers);
                     }
+                    #[cfg(feature = "markers")]
+                    SpentMarkers(spent) => {
+                        self.marker_window.spent_markers = spent;
+                    }
                     #[cfg(feature = "markers-edit")]
                     GiveMarkerPaths(paths) => {
                         self.edit_marker_window.set_filenames(paths);
                     }
+                    #[cfg(feature = "timers-edit")]
+                    OpenEditTimer(timer) => match timer {
+                        None => self.edit_timer_window.open(),
+                        Some(timer) => self.edit_timer_window.open_edit(timer),
+                    },
                     OpenableError(key, err) => {
                         self.state_errors.insert(key, err);
                     }
@@ -138,10 +179,19 @@ impl RenderState {
                         self.primary_window.data_sources_tab.checking_for_updates =
                             checking_for_updates;
                     }
+                    TraceRecording(recording) => {
+                        self.primary_window.timer_tab.trace_recording = recording;
+                    }
+                    TraceReplaying(replaying) => {
+                        self.primary_window.timer_tab.trace_replaying = replaying;
+                    }
                     TimerData(timers) => {
                         self.primary_window.timer_tab.timer_selection = None;
                         self.primary_window.timer_tab.timers_update(timers);
                     }
+                    AttemptStats(stats) => {
+                        self.primary_window.timer_tab.attempt_stats = stats;
+                    }
                     #[cfg(feature = "markers")]
                     MarkerData(markers) => {
                         self.primary_window.marker_tab.marker_selection = None;
@@ -150,15 +200,23 @@ impl RenderState {
                         self.edit_marker_window.category_update(categories);
                         self.primary_window.marker_tab.marker_update(markers);
                     }
+                    #[cfg(feature = "markers")]
+                    AgentPresets(presets) => {
+                        self.primary_window.marker_tab.agent_presets_update(presets);
+                    }
+                    #[cfg(feature = "markers")]
+                    AgentSteps(steps, current) => {
+                        self.primary_window.marker_tab.agent_steps = (steps, current);
+                    }
+                    #[cfg(feature = "markers")]
+                    AgentTarget(account) => {
+                        self.primary_window.marker_tab.agent_target = account;
+                    }
                     AlertStart(alert) => {
-                        self.alert = Some(alert);
+                        self.alert_queue.push(alert);
                     }
                     AlertEnd(timer_file) => {
-                        if let Some(alert) = &self.alert {
-                            if Arc::ptr_eq(&alert.timer, &timer_file) {
-                                self.alert = None;
-                            }
-                        }
+                        self.alert_queue.end(&timer_file);
                     }
                     AlertFeed(phase_state) => {
                         self.timer_window.new_phase(phase_state);
@@ -166,6 +224,12 @@ impl RenderState {
                     AlertReset(timer) => {
                         self.timer_window.remove_phase(timer);
                     }
+                    AlertQueue(timer, queue) => {
+                        self.timer_window.alert_queue(timer, queue);
+                    }
+                    AlertPlayer(player) => {
+                        self.timer_window.player = player;
+                    }
                     Quit => {
                         self.quit();
                         return;
@@ -175,6 +239,7 @@ impl RenderState {
             Err(_error) => (),
         }
         self.handle_alert(ui, io);
+        DirectionOverlay::draw(ui, &self.timer_window.phase_states);
         self.timer_window.draw(ui);
         self.primary_window
             .draw(ui, &mut self.timer_window, &mut self.state_errors);
@@ -182,6 +247,8 @@ impl RenderState {
         self.marker_window.draw(ui);
         #[cfg(feature = "markers-edit")]
         self.edit_marker_window.draw(ui);
+        #[cfg(feature = "timers-edit")]
+        self.edit_timer_window.draw(ui);
         let mut items_to_delete = Vec::new();
         for (entry_name, errory) in &self.state_errors {
             ui.open_popup(entry_name);
@@ -213,41 +280,53 @@ impl RenderState {
             };
             Image::new(icon.id(), size).build(ui);
             ui.same_line();
-        } else if let Some(data) = marker_icon_data(marker.clone()) {
-            Controller::try_send(ControllerEvent::LoadTextureIntegrated(
-                marker.to_string(),
-                data,
-            ));
+        } else if Self::request_texture(&marker.to_string()) {
+            if let Some(data) = marker_icon_data(marker.clone()) {
+                Controller::try_send(ControllerEvent::LoadTextureIntegrated(
+                    marker.to_string(),
+                    data,
+                ));
+            }
         }
     }
 
+    /// Whether the texture still needs loading, which is only true the first time it's asked about.
+    fn request_texture(id: &str) -> bool {
+        REQUESTED_TEXTURES
+            .lock()
+            .is_ok_and(|mut requested| requested.insert(id.to_string()))
+    }
+
+    /// Draws an icon given relative to the file at `path`, followed by `same_line`.
     pub fn icon(
         ui: &Ui,
         height: Option<f32>,
         alert_icon: Option<&RelativePathBuf>,
         path: Option<&PathBuf>,
     ) {
-        if let Some(icon) = alert_icon {
-            if let Some(path) = path {
-                let gooey = IMGUI_TEXTURES.get().unwrap();
-                let gooey_lock = gooey.read().unwrap();
-                let path_str = icon.as_str();
-                if let Some(icon) = gooey_lock.get(path_str) {
-                    //if let Some(icon) = get_texture(icon.as_str()) {
-                    let size = match height {
-                        Some(height) => [height, height],
-                        None => icon.size(),
-                    };
-                    Image::new(icon.id(), size).build(ui);
-                    ui.same_line();
-                } else {
-                    Controller::try_send(ControllerEvent::LoadTexture(
-                        icon.clone(),
-                        path.to_path_buf(),
-                    ));
-                }
-            }
+        let (Some(icon), Some(path)) = (alert_icon, path) else {
+            return;
         };
+        let Some(base) = path.parent() else {
+            return;
+        };
+        // packs can share relative paths like "icons/boss.png", so textures go by the full path
+        let id = icon.to_path(base).to_string_lossy().to_string();
+        let gooey = IMGUI_TEXTURES.get().unwrap();
+        let gooey_lock = gooey.read().unwrap();
+        if let Some(texture) = gooey_lock.get(&id) {
+            let size = match height {
+                Some(height) => [height, height],
+                None => texture.size(),
+            };
+            Image::new(texture.id(), size).build(ui);
+            ui.same_line();
+        } else if Self::request_texture(&id) {
+            Controller::try_send(ControllerEvent::LoadTexture(
+                icon.clone(),
+                path.to_path_buf(),
+            ));
+        }
     }
     pub fn draw_open_button<S: AsRef<str> + std::fmt::Display>(
         state_errors: &mut HashMap<String, anyhow::Error>,
@@ -336,26 +415,49 @@ impl RenderState {
     }
 
     fn handle_alert(&mut self, ui: &Ui, io: &Io) {
-        if let Some(alert) = &self.alert {
-            let message = &alert.message;
+        let max_text_alerts = SETTINGS
+            .get()
+            .and_then(|settings| settings.try_read().ok())
+            .map(|settings| settings.max_text_alerts as usize);
+        if let Some(max_text_alerts) = max_text_alerts {
+            self.max_text_alerts = max_text_alerts.max(1);
+        }
+        let messages: Vec<_> = self
+            .alert_queue
+            .visible(self.max_text_alerts)
+            .into_iter()
+            .map(|alert| alert.message.clone())
+            .collect();
+        if !messages.is_empty() {
             let nexus_link = read_nexus_link().unwrap();
             let imfont_pointer = nexus_link.font_big;
             let imfont = unsafe { Font::from_raw(&*imfont_pointer) };
-            Self::render_alert(ui, io, message, imfont.id(), imfont.scale);
+            Self::render_alerts(ui, io, &messages, imfont.id(), imfont.scale);
         }
     }
-    pub fn render_alert(
+    /// Stacks `texts` downwards from above the middle of the screen, each centred.
+    pub fn render_alerts(
         ui: &Ui,
         io: &nexus::imgui::Io,
-        text: &String,
+        texts: &[String],
         font: FontId,
         font_scale: f32,
     ) {
         use WindowFlags;
         let font_handle = ui.push_font(font);
         let fb_scale = io.display_framebuffer_scale;
-        let [text_width, text_height] = ui.calc_text_size(text);
-        let text_width = text_width * font_scale;
+        let text_sizes: Vec<_> = texts
+            .iter()
+            .map(|text| {
+                let [text_width, text_height] = ui.calc_text_size(text);
+                [text_width * font_scale, text_height]
+            })
+            .collect();
+        let text_width = text_sizes
+            .iter()
+            .map(|[text_width, _]| *text_width)
+            .fold(0.0, f32::max);
+        let text_height: f32 = text_sizes.iter().map(|[_, text_height]| text_height).sum();
         let offset_x = text_width / 2.0;
         let [game_width, game_height] = io.display_size;
         let centre_x = game_width / 2.0;
@@ -378,7 +480,11 @@ impl RenderState {
             .position([text_x, text_y], Condition::Always)
             .size([text_width * 1.25, text_height * 2.0], Condition::Always)
             .build(ui, || {
-                ui.text(text);
+                for (text, [line_width, _]) in texts.iter().zip(&text_sizes) {
+                    let [cursor_x, cursor_y] = ui.cursor_pos();
+                    ui.set_cursor_pos([cursor_x + (text_width - line_width) / 2.0, cursor_y]);
+                    ui.text(text);
+                }
             });
         font_handle.pop();
     }
@@ -4,14 +4,17 @@ use {
         controller::{ControllerEvent, Controller},
         fl,
         render::RenderState,
-        settings::{RemoteSource, TimerSettings},
-        timer::TimerFile,
+        settings::{OverrideColour, TimerOverrideChange, TimerSettings},
+        timer::{AttemptStats, BlishColour, TimerFile},
         SETTINGS,
     },
     glam::Vec2,
     indexmap::IndexMap,
     nexus::{
-        imgui::{ChildWindow, Condition, Selectable, TreeNode, TreeNodeFlags, Ui, WindowFlags},
+        imgui::{
+            ChildWindow, Condition, Selectable, Slider, TableColumnSetup, TableFlags, TreeNode,
+            TreeNodeFlags, Ui, WindowFlags,
+        },
         paths::get_addon_dir,
     },
     std::{
@@ -20,12 +23,18 @@ use {
     },
 };
 
+#[cfg(feature = "timers-edit")]
+use crate::render::RenderEvent;
+
 pub struct TimerTabState {
     timers: Vec<Arc<TimerFile>>,
     categories: IndexMap<String, Vec<Arc<TimerFile>>>,
     pub timer_selection: Option<Arc<TimerFile>>,
     category_status: HashSet<String>,
-    sources_to_timers: IndexMap<Arc<RemoteSource>, Vec<Arc<TimerFile>>>,
+    sources_to_timers: IndexMap<String, Vec<Arc<TimerFile>>>,
+    pub trace_recording: bool,
+    pub trace_replaying: bool,
+    pub attempt_stats: HashMap<String, AttemptStats>,
     //search_string: String,
 }
 
@@ -37,6 +46,9 @@ impl TimerTabState {
             timer_selection: Default::default(),
             category_status: Default::default(),
             sources_to_timers: Default::default(),
+            trace_recording: false,
+            trace_replaying: false,
+            attempt_stats: Default::default(),
         }
     }
 
@@ -67,6 +79,35 @@ impl TimerTabState {
         if ui.button(fl!("reload-timers")) {
             Controller::try_send(ControllerEvent::ReloadTimers);
         }
+        #[cfg(feature = "timers-edit")]
+        {
+            ui.same_line();
+            if ui.button(fl!("new-timer")) {
+                RenderState::try_send(RenderEvent::OpenEditTimer(None));
+            }
+        }
+        let traces_dir = addon_dir.join("traces");
+        let traces_dir = traces_dir.to_string_lossy().to_string();
+        RenderState::draw_open_button(
+            state_errors,
+            ui,
+            fl!("open-button", kind = "traces folder"),
+            traces_dir,
+        );
+        ui.same_line();
+        let record_text = match self.trace_recording {
+            true => fl!("trace-record-stop"),
+            false => fl!("trace-record-start"),
+        };
+        if ui.button(record_text) {
+            Controller::try_send(ControllerEvent::TraceRecordToggle);
+        }
+        ui.same_line();
+        if self.trace_replaying {
+            ui.text_disabled(fl!("trace-replaying"));
+        } else if ui.button(fl!("trace-replay")) {
+            Controller::try_send(ControllerEvent::TraceReplay(None));
+        }
         /*let button_text = match timer_window_state.open {
             true => "Close Timers",
             false => "Open Timers",
@@ -80,7 +121,7 @@ impl TimerTabState {
         }
         ui.same_line();
         if ui.button("Reset Timers") {
-            Controller::try_send(ControllerEvent::TimerReset);
+            Controller::try_send(ControllerEvent::TimerReset(None));
             timer_window_state.reset_phases();
         }*/
         if self.category_status.len() != self.categories.keys().len() {
@@ -240,7 +281,7 @@ impl TimerTabState {
                     RenderState::font_text(
                         "font",
                         ui,
-                        &fl!("map-id-arg", id = selected_timer.map_id),
+                        &fl!("map-id-arg", id = selected_timer.map_ids.to_string()),
                     );
                     ui.dummy([4.0; 2]);
                     ui.separator();
@@ -260,12 +301,217 @@ impl TimerTabState {
                         if ui.button(button_text) {
                             Controller::try_send(ControllerEvent::TimerToggle(selected_timer.id.clone()));
                         }
+                        #[cfg(feature = "timers-edit")]
+                        {
+                            ui.same_line();
+                            if ui.button(fl!("edit-timer-button")) {
+                                RenderState::try_send(RenderEvent::OpenEditTimer(Some(
+                                    selected_timer.clone(),
+                                )));
+                            }
+                        }
+                        let settings_for_timer = settings_for_timer.cloned().unwrap_or_default();
+                        ui.dummy([4.0; 2]);
+                        Self::draw_overrides(ui, selected_timer, &settings_for_timer);
                     }
+                    ui.dummy([4.0; 2]);
+                    Self::draw_attempts(
+                        ui,
+                        selected_timer,
+                        self.attempt_stats.get(&selected_timer.id),
+                    );
                 } else {
                     ui.text(&fl!("select-a-timer"));
                 }
             });
     }
+    fn draw_overrides(ui: &Ui, timer: &TimerFile, settings: &TimerSettings) {
+        let send = |change| {
+            Controller::try_send(ControllerEvent::TimerOverride(timer.id.clone(), change));
+        };
+        let overrides_closure = || {
+            let mut offset = settings.offset;
+            if Slider::new(&fl!("timer-offset"), -30.0, 30.0)
+                .display_format("%.1fs")
+                .build(ui, &mut offset)
+            {
+                send(TimerOverrideChange::Offset(offset));
+            }
+            for (phase_index, phase) in timer.phases.iter().enumerate() {
+                for (index, alert) in phase.alerts.iter().enumerate() {
+                    let key = TimerSettings::alert_key(phase_index, index);
+                    let mut alert_override = settings
+                        .alert_override(phase_index, index)
+                        .cloned()
+                        .unwrap_or_default();
+                    let pushy = ui.push_id(&key);
+                    let text = alert.alert.as_ref().or(alert.warning.as_ref());
+                    ui.text_wrapped(fl!(
+                        "alert-override",
+                        phase = phase.name.clone(),
+                        text = text.cloned().unwrap_or_default()
+                    ));
+                    let mut changed = false;
+                    if alert.alert.is_some() {
+                        changed |= ui.checkbox(&fl!("mute-alert"), &mut alert_override.mute_alert);
+                        ui.same_line();
+                    }
+                    if alert.warning.is_some() {
+                        changed |=
+                            ui.checkbox(&fl!("mute-warning"), &mut alert_override.mute_warning);
+                        ui.same_line();
+                    }
+                    changed |= Slider::new(&fl!("offset"), -30.0, 30.0)
+                        .display_format("%.1fs")
+                        .build(ui, &mut alert_override.offset);
+                    if let Some(text) = &alert.alert {
+                        changed |= Self::draw_text_override(
+                            ui,
+                            &fl!("alert-text"),
+                            text,
+                            &mut alert_override.alert,
+                        );
+                        changed |= Self::draw_colour_override(
+                            ui,
+                            &fl!("alert-colour"),
+                            alert.alert_color,
+                            &mut alert_override.alert_color,
+                        );
+                    }
+                    if let Some(text) = &alert.warning {
+                        changed |= Self::draw_text_override(
+                            ui,
+                            &fl!("warning-text"),
+                            text,
+                            &mut alert_override.warning,
+                        );
+                        changed |= Self::draw_colour_override(
+                            ui,
+                            &fl!("warning-colour"),
+                            alert.warning_color,
+                            &mut alert_override.warning_color,
+                        );
+                    }
+                    changed |= Self::draw_colour_override(
+                        ui,
+                        &fl!("fill-colour"),
+                        alert.fill_color,
+                        &mut alert_override.fill_color,
+                    );
+                    if changed {
+                        send(TimerOverrideChange::Alert(key, alert_override));
+                    }
+                    pushy.pop();
+                }
+            }
+            if ui.button(&fl!("reset-overrides")) {
+                send(TimerOverrideChange::Clear);
+            }
+        };
+        let _overrides = TreeNode::new(&fl!("overrides"))
+            .flags(TreeNodeFlags::FRAMED)
+            .tree_push_on_open(true)
+            .build(ui, overrides_closure);
+    }
+
+    /// Left empty, the pack's own text is kept.
+    fn draw_text_override(ui: &Ui, label: &str, original: &str, text: &mut Option<String>) -> bool {
+        let mut entry = text.clone().unwrap_or_default();
+        let changed = ui.input_text(label, &mut entry).hint(original).build();
+        if changed {
+            *text = Some(entry).filter(|entry| !entry.is_empty());
+        }
+        changed
+    }
+
+    /// Ticking the box starts from the pack's own colour, or white if it has none.
+    fn draw_colour_override(
+        ui: &Ui,
+        label: &str,
+        original: Option<BlishColour>,
+        colour: &mut Option<OverrideColour>,
+    ) -> bool {
+        let mut enabled = colour.is_some();
+        let mut changed = ui.checkbox(label, &mut enabled);
+        if changed {
+            *colour = enabled.then(|| {
+                OverrideColour::from_imgcolor(original.map_or([1.0; 4], BlishColour::imgcolor))
+            });
+        }
+        if let Some(colour) = colour {
+            ui.same_line();
+            let mut value = colour.imgcolor();
+            if ui.color_edit4(format!("##{label}"), &mut value) {
+                *colour = OverrideColour::from_imgcolor(value);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn format_seconds(seconds: Option<f32>) -> String {
+        match seconds {
+            Some(seconds) => {
+                let minutes = (seconds / 60.0).floor();
+                format!("{}:{:04.1}", minutes as u32, seconds - minutes * 60.0)
+            }
+            None => fl!("not-applicable"),
+        }
+    }
+
+    fn draw_attempts(ui: &Ui, timer: &TimerFile, stats: Option<&AttemptStats>) {
+        let attempts_closure = || {
+            let Some(stats) = stats.filter(|stats| stats.attempts > 0) else {
+                ui.text_wrapped(&fl!("no-attempts"));
+                return;
+            };
+            ui.text_wrapped(fl!(
+                "attempts-summary",
+                attempts = stats.attempts,
+                finished = stats.finished,
+                rate = format!("{:.0}", stats.success_rate() * 100.0)
+            ));
+            ui.text_wrapped(fl!(
+                "attempts-best",
+                best = Self::format_seconds(stats.best),
+                median = Self::format_seconds(stats.median)
+            ));
+            let table_flags = TableFlags::RESIZABLE | TableFlags::ROW_BG | TableFlags::BORDERS;
+            let table_name = format!("attempts_for_{}", timer.id);
+            let table_token = ui.begin_table_header_with_flags(
+                &table_name,
+                [
+                    TableColumnSetup::new(&fl!("phase")),
+                    TableColumnSetup::new(&fl!("best")),
+                    TableColumnSetup::new(&fl!("median")),
+                    TableColumnSetup::new(&fl!("resets")),
+                ],
+                table_flags,
+            );
+            ui.table_next_column();
+            for phase in &stats.phases {
+                ui.text_wrapped(&phase.name);
+                ui.table_next_column();
+                ui.text(Self::format_seconds(phase.best));
+                ui.table_next_column();
+                ui.text(Self::format_seconds(phase.median));
+                ui.table_next_column();
+                ui.text(phase.resets.to_string());
+                ui.table_next_column();
+            }
+            if let Some(token) = table_token {
+                token.end();
+            }
+            if ui.button(&fl!("clear-attempts")) {
+                Controller::try_send(ControllerEvent::ClearAttempts(timer.id.clone()));
+            }
+        };
+        let _attempts = TreeNode::new(&fl!("attempts"))
+            .flags(TreeNodeFlags::FRAMED)
+            .tree_push_on_open(true)
+            .build(ui, attempts_closure);
+    }
+
     pub fn timers_update(&mut self, timers: Vec<Arc<TimerFile>>) {
         self.timers = timers;
         self.sources_to_timers.clear();
@@ -273,11 +519,9 @@ impl TimerTabState {
         for timer in &self.timers {
             if let Some(association) = &timer.association {
                 self.sources_to_timers
-                    .entry(association.clone())
-                    .or_default();
-                if let Some(val) = self.sources_to_timers.get_mut(association) {
-                    val.push(timer.clone());
-                };
+                    .entry(association.to_string())
+                    .or_default()
+                    .push(timer.clone());
             }
             self.categories.entry(timer.category.clone()).or_default();
             if let Some(val) = self.categories.get_mut(&timer.category) {
@@ -3,18 +3,21 @@ use {
     crate::{
         fl,
         settings::ProgressBarSettings,
-        timer::{PhaseState, TimerAlert, TimerFile},
+        timer::{AlertPlayer, PhaseState, QueuedAlert, TimerAlert, TimerFile},
         ControllerEvent, Controller, SETTINGS,
     },
     glam::Vec2,
     nexus::imgui::{ProgressBar, StyleColor, Ui, Window},
     std::sync::Arc,
+    tokio::time::Instant,
 };
 
 pub struct TimerWindowState {
     pub open: bool,
     pub progress_bar: ProgressBarSettings,
     pub phase_states: Vec<PhaseState>,
+    pub queued_alerts: Vec<(Arc<TimerFile>, Vec<QueuedAlert>)>,
+    pub player: AlertPlayer,
 }
 
 impl TimerWindowState {
@@ -23,6 +26,8 @@ impl TimerWindowState {
             open: false,
             progress_bar: Default::default(),
             phase_states: Default::default(),
+            queued_alerts: Default::default(),
+            player: Default::default(),
         }
     }
 
@@ -39,7 +44,7 @@ impl TimerWindowState {
                 .build(ui, || {
                     if !self.phase_states.is_empty() {
                         if ui.button(fl!("reset-timers")) {
-                            Controller::try_send(ControllerEvent::TimerReset);
+                            Controller::try_send(ControllerEvent::TimerReset(None));
                             self.reset_phases();
                         }
                         ui.dummy([2.0; 2]);
@@ -51,12 +56,19 @@ impl TimerWindowState {
                     for ps in &self.phase_states {
                         for alert in ps.alerts.iter() {
                             if self.progress_bar.stock {
-                                Self::stock_progress_bar(&self.progress_bar, alert, ui, ps);
+                                Self::stock_progress_bar(
+                                    &self.progress_bar,
+                                    &self.player,
+                                    alert,
+                                    ui,
+                                    ps,
+                                );
                             } else {
-                                Self::progress_bar(&self.progress_bar, alert, ui, ps);
+                                Self::progress_bar(&self.progress_bar, &self.player, alert, ui, ps);
                             }
                         }
                     }
+                    self.draw_queued_alerts(ui);
                 });
         }
 
@@ -69,10 +81,17 @@ impl TimerWindowState {
         }
     }
 
-    fn progress_bar(settings: &ProgressBarSettings, alert: &TimerAlert, ui: &Ui, ps: &PhaseState) {
+    fn progress_bar(
+        settings: &ProgressBarSettings,
+        player: &AlertPlayer,
+        alert: &TimerAlert,
+        ui: &Ui,
+        ps: &PhaseState,
+    ) {
         let start = ps.start;
         let height = settings.height;
-        if let Some(percent) = alert.percentage(start) {
+        let now = Instant::now();
+        if let Some(percent) = alert.percentage(start, now) {
             let mut widget_pos: Vec2 = Vec2::new(0.0, 0.0);
             if !settings.centre_after {
                 widget_pos = Vec2::from(ui.cursor_pos());
@@ -100,7 +119,7 @@ impl TimerWindowState {
                 .build(ui);
             let window_size = Vec2::from(ui.window_size());
             let widget_size = window_size.with_y(height);
-            let text = alert.progress_bar_text(start);
+            let text = alert.progress_bar_text(ps, now, settings.remaining_format, player);
             RenderState::offset_font_text(
                 &settings.font.to_string(),
                 ui,
@@ -118,13 +137,15 @@ impl TimerWindowState {
 
     fn stock_progress_bar(
         settings: &ProgressBarSettings,
+        player: &AlertPlayer,
         alert: &TimerAlert,
         ui: &Ui,
         ps: &PhaseState,
     ) {
         let start = ps.start;
         let height = settings.height;
-        if let Some(percent) = alert.percentage(start) {
+        let now = Instant::now();
+        if let Some(percent) = alert.percentage(start, now) {
             RenderState::icon(
                 ui,
                 Some(height),
@@ -141,7 +162,12 @@ impl TimerWindowState {
             }
             ProgressBar::new(percent)
                 .size([-1.0, height])
-                .overlay_text(alert.progress_bar_text(start))
+                .overlay_text(alert.progress_bar_text(
+                    ps,
+                    now,
+                    settings.remaining_format,
+                    player,
+                ))
                 .build(ui);
             for token in colour_tokens {
                 token.pop();
@@ -149,6 +175,33 @@ impl TimerWindowState {
         }
     }
 
+    fn draw_queued_alerts(&self, ui: &Ui) {
+        if self.queued_alerts.is_empty() {
+            return;
+        }
+        ui.dummy([4.0; 2]);
+        ui.separator();
+        ui.text(fl!("upcoming-alerts"));
+        let now = Instant::now();
+        for (_timer, queue) in &self.queued_alerts {
+            for alert in queue {
+                let remaining = alert.show_at.saturating_duration_since(now);
+                ui.text_wrapped(fl!(
+                    "upcoming-alert",
+                    message = alert.message.clone(),
+                    remaining = format!("{:.1}", remaining.as_secs_f32())
+                ));
+            }
+        }
+    }
+
+    pub fn alert_queue(&mut self, timer: Arc<TimerFile>, queue: Vec<QueuedAlert>) {
+        self.queued_alerts.retain(|(t, _)| !Arc::ptr_eq(t, &timer));
+        if !queue.is_empty() {
+            self.queued_alerts.push((timer, queue));
+        }
+    }
+
     pub fn new_phase(&mut self, phase_state: PhaseState) {
         self.phase_states.push(phase_state);
     }
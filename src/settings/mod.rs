@@ -1,4 +1,5 @@
 mod needs_update;
+mod overrides;
 mod progress_bar_config;
 mod settings_struct;
 mod source;
@@ -13,5 +14,5 @@ pub use {
     },
     source::{GitHubSource, RemoteSource, Source},
     sources::{SourceKind, SourcesFile},
-    v1::{RemoteState, TimerSettings},
+    v1::{AlertOverride, OverrideColour, RemoteState, TimerOverrideChange, TimerSettings},
 };
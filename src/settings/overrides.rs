@@ -0,0 +1,39 @@
+use {
+    super::{AlertOverride, OverrideColour, TimerSettings},
+    crate::timer::{AlertOverrides, BlishColour, TimerOverrides},
+};
+
+impl TimerSettings {
+    /// The overrides in the shape the timer machines apply them.
+    pub fn overrides(&self) -> TimerOverrides {
+        TimerOverrides {
+            offset: self.offset,
+            alerts: self
+                .alerts
+                .iter()
+                .filter_map(|(key, alert)| Some((Self::parse_alert_key(key)?, alert.overrides())))
+                .collect(),
+        }
+    }
+}
+
+impl AlertOverride {
+    fn overrides(&self) -> AlertOverrides {
+        AlertOverrides {
+            offset: self.offset,
+            mute_alert: self.mute_alert,
+            mute_warning: self.mute_warning,
+            alert: self.alert.clone(),
+            warning: self.warning.clone(),
+            alert_colour: self.alert_color.map(OverrideColour::colour),
+            warning_colour: self.warning_color.map(OverrideColour::colour),
+            fill_colour: self.fill_color.map(OverrideColour::colour),
+        }
+    }
+}
+
+impl OverrideColour {
+    fn colour(self) -> BlishColour {
+        BlishColour::new(self.red, self.green, self.blue, self.alpha)
+    }
+}
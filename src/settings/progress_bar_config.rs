@@ -1,5 +1,5 @@
 use {
-    crate::render::TextFont,
+    crate::{render::TextFont, timer::RemainingFormat},
     serde::{Deserialize, Serialize},
 };
 
@@ -27,6 +27,8 @@ pub struct ProgressBarSettings {
     pub shadow: bool,
     #[serde(default)]
     pub centre_after: bool,
+    #[serde(default)]
+    pub remaining_format: RemainingFormat,
 }
 
 impl Default for ProgressBarSettings {
@@ -37,6 +39,7 @@ impl Default for ProgressBarSettings {
             stock: false,
             shadow: true,
             centre_after: false,
+            remaining_format: Default::default(),
         }
     }
 }
@@ -57,4 +60,7 @@ impl ProgressBarSettings {
     pub fn set_centre_after(&mut self, centre_after: bool) {
         self.centre_after = centre_after;
     }
+    pub fn set_remaining_format(&mut self, remaining_format: RemainingFormat) {
+        self.remaining_format = remaining_format;
+    }
 }
@@ -1,5 +1,8 @@
 use {
-    super::{ProgressBarSettings, RemoteSource, RemoteState, Source, SourceKind, TimerSettings},
+    super::{
+        ProgressBarSettings, RemoteSource, RemoteState, Source, SourceKind, TimerOverrideChange,
+        TimerSettings,
+    },
     crate::{controller::ProgressBarStyleChange, SETTINGS, SOURCES},
     anyhow::anyhow,
     chrono::{DateTime, Utc},
@@ -114,6 +117,14 @@ impl fmt::Display for MarkerAutoPlaceSettings {
     }
 }
 
+fn default_max_text_alerts() -> u32 {
+    3
+}
+
+fn default_api_port() -> u16 {
+    7707
+}
+
 #[derive(Deserialize, Serialize, TryMigrate, Default, Debug, Clone)]
 #[try_migrate(from = None)]
 pub struct Settings {
@@ -139,6 +150,15 @@ pub struct Settings {
     pub enable_katrender: bool,
     #[serde(default)]
     pub marker_autoplace: MarkerAutoPlaceSettings,
+    #[serde(default = "default_max_text_alerts")]
+    pub max_text_alerts: u32,
+    #[serde(default)]
+    pub enable_api: bool,
+    #[serde(default = "default_api_port")]
+    pub api_port: u16,
+    /// Web pages, such as `https://example.com`, allowed to open a WebSocket to the state API.
+    #[serde(default)]
+    pub api_allowed_origins: Vec<String>,
 }
 
 impl Settings {
@@ -238,7 +258,13 @@ impl Settings {
         if let Some(entry_mut) = self.timers.get_mut(&timer) {
             entry_mut.disable();
         } else {
-            self.timers.insert(timer, TimerSettings { disabled: true });
+            self.timers.insert(
+                timer,
+                TimerSettings {
+                    disabled: true,
+                    ..Default::default()
+                },
+            );
         }
         let _ = self.save(&self.addon_dir).await;
     }
@@ -250,6 +276,10 @@ impl Settings {
         }
         let _ = self.save(&self.addon_dir).await;
     }
+    pub async fn change_timer_override(&mut self, timer: String, change: TimerOverrideChange) {
+        self.timers.entry(timer).or_default().change_override(change);
+        let _ = self.save(&self.addon_dir).await;
+    }
     pub async fn toggle_marker(&mut self, marker: String) -> bool {
         let entry = self.markers.entry(marker.clone()).or_default();
         let new_state = entry.toggle();
@@ -273,6 +303,29 @@ impl Settings {
         }
         let _ = self.save(&self.addon_dir).await;
     }
+    /**
+     * Copies marker settings from the old text ids over to the marker sets' uuids, given
+     * pairs of (legacy id, id). Sets with the same text used to share an id, so each of
+     * them gets its own copy. The old entries are kept, for anything that still goes by
+     * them, such as sets in files that haven't been edited in Taimi yet.
+     */
+    pub async fn migrate_marker_ids(&mut self, ids: &[(String, String)]) {
+        let mut migrated = 0;
+        for (legacy_id, id) in ids {
+            if legacy_id == id || self.markers.contains_key(id) {
+                continue;
+            }
+            if let Some(marker) = self.markers.get(legacy_id).cloned() {
+                self.markers.insert(id.clone(), marker);
+                migrated += 1;
+            }
+        }
+        if migrated == 0 {
+            return;
+        }
+        log::info!("Copied the settings of {migrated} marker sets over to their uuids.");
+        let _ = self.save(&self.addon_dir).await;
+    }
 
     #[allow(dead_code)]
     pub async fn get_status_for(&self, source: &RemoteSource) -> Option<&RemoteState> {
@@ -335,11 +388,28 @@ impl Settings {
             Shadow(t) => self.progress_bar.set_shadow(t),
             Height(h) => self.progress_bar.set_height(h),
             Font(f) => self.progress_bar.set_font(f),
+            RemainingFormat(f) => self.progress_bar.set_remaining_format(f),
         }
         let _ = self.save(&self.addon_dir).await;
         self.progress_bar.clone()
     }
 
+    pub async fn set_max_text_alerts(&mut self, max_text_alerts: u32) {
+        self.max_text_alerts = max_text_alerts;
+        let _ = self.save(&self.addon_dir).await;
+    }
+
+    pub async fn set_api(&mut self, enabled: bool, port: u16) {
+        self.enable_api = enabled;
+        self.api_port = port;
+        let _ = self.save(&self.addon_dir).await;
+    }
+
+    pub async fn set_api_allowed_origins(&mut self, allowed_origins: Vec<String>) {
+        self.api_allowed_origins = allowed_origins;
+        let _ = self.save(&self.addon_dir).await;
+    }
+
     pub async fn toggle_katrender(&mut self) {
         self.enable_katrender = !self.enable_katrender;
     }
@@ -385,6 +455,10 @@ impl Settings {
             primary_window_open: false,
             enable_katrender: false,
             marker_autoplace: Default::default(),
+            max_text_alerts: default_max_text_alerts(),
+            enable_api: false,
+            api_port: default_api_port(),
+            api_allowed_origins: Default::default(),
         }
     }
     pub async fn load(addon_dir: &Path) -> anyhow::Result<Self> {
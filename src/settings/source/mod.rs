@@ -8,6 +8,7 @@ use {
         io,
         path::{Path, PathBuf},
     },
+    taimi_timer::TimerSource,
     tokio::fs::{create_dir_all, remove_dir_all},
     tokio_tar::Archive,
     tokio_util::io::StreamReader,
@@ -38,6 +39,8 @@ impl RemoteSource {
     }
 }
 
+impl TimerSource for RemoteSource {}
+
 impl fmt::Display for RemoteSource {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let RemoteSource::GitHub(inner) = self;
@@ -1,4 +1,7 @@
 mod remote_state;
 mod timer;
 
-pub use {remote_state::RemoteState, timer::TimerSettings};
+pub use {
+    remote_state::RemoteState,
+    timer::{AlertOverride, OverrideColour, TimerOverrideChange, TimerSettings},
+};
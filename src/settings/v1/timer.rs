@@ -1,9 +1,62 @@
-use serde::{Deserialize, Serialize};
+use {
+    serde::{Deserialize, Serialize},
+    std::collections::HashMap,
+};
 
 #[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
 pub struct TimerSettings {
     #[serde(default)]
     pub disabled: bool,
+    /// Seconds added to every alert in the timer; negative values bring them forward.
+    #[serde(default)]
+    pub offset: f32,
+    /// Keyed by [TimerSettings::alert_key], so that they outlive pack updates.
+    #[serde(default)]
+    pub alerts: HashMap<String, AlertOverride>,
+}
+
+/// A user's changes to one of a phase's alerts, covering both its alert and its warning.
+#[derive(Deserialize, Serialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertOverride {
+    #[serde(default)]
+    pub offset: f32,
+    #[serde(default)]
+    pub mute_alert: bool,
+    #[serde(default)]
+    pub mute_warning: bool,
+    #[serde(default)]
+    pub alert: Option<String>,
+    #[serde(default)]
+    pub warning: Option<String>,
+    #[serde(default)]
+    pub alert_color: Option<OverrideColour>,
+    #[serde(default)]
+    pub warning_color: Option<OverrideColour>,
+    #[serde(default)]
+    pub fill_color: Option<OverrideColour>,
+}
+
+/// Stored the same way as a timer file's colours, but owned by the settings so that
+/// changes to timers don't change the saved format.
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct OverrideColour {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    #[serde(default = "default_alpha")]
+    pub alpha: f32,
+}
+
+fn default_alpha() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Clone)]
+pub enum TimerOverrideChange {
+    Offset(f32),
+    Alert(String, AlertOverride),
+    Clear,
 }
 
 impl TimerSettings {
@@ -17,4 +70,57 @@ impl TimerSettings {
         self.disabled = !self.disabled;
         self.disabled
     }
+
+    /// Alerts are identified by their phase's position in the timer and their position within it.
+    pub fn alert_key(phase: usize, index: usize) -> String {
+        format!("{phase}:{index}")
+    }
+
+    /// The phase and alert positions an [TimerSettings::alert_key] was made from.
+    pub fn parse_alert_key(key: &str) -> Option<(usize, usize)> {
+        let (phase, index) = key.split_once(':')?;
+        Some((phase.parse().ok()?, index.parse().ok()?))
+    }
+
+    pub fn alert_override(&self, phase: usize, index: usize) -> Option<&AlertOverride> {
+        self.alerts.get(&Self::alert_key(phase, index))
+    }
+
+    pub fn change_override(&mut self, change: TimerOverrideChange) {
+        use TimerOverrideChange::*;
+        match change {
+            Offset(offset) => self.offset = offset,
+            Alert(key, alert) if alert == AlertOverride::default() => {
+                self.alerts.remove(&key);
+            }
+            Alert(key, alert) => {
+                self.alerts.insert(key, alert);
+            }
+            Clear => {
+                self.offset = 0.0;
+                self.alerts.clear();
+            }
+        }
+    }
+}
+
+impl OverrideColour {
+    pub fn imgcolor(self) -> [f32; 4] {
+        [
+            self.red as f32 / 255.0,
+            self.green as f32 / 255.0,
+            self.blue as f32 / 255.0,
+            self.alpha,
+        ]
+    }
+
+    pub fn from_imgcolor([red, green, blue, alpha]: [f32; 4]) -> Self {
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Self {
+            red: channel(red),
+            green: channel(green),
+            blue: channel(blue),
+            alpha,
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use {
+    crate::{
+        space::{
+            dx11::VertexBuffer,
+            resources::{Model, Texture, Vertex},
+        },
+        timer::{PhaseState, TimerDirection},
+    },
+    anyhow::Context,
+    glam::{Vec2, Vec3},
+    std::sync::Arc,
+    windows::Win32::Graphics::{
+        Direct3D::D3D_PRIMITIVE_TOPOLOGY_TRIANGLESTRIP,
+        Direct3D11::{ID3D11Device, ID3D11DeviceContext},
+    },
+};
+
+/// Matches the trail width used for pack trails.
+const GUIDE_WIDTH: f32 = 20.0 * 0.0254;
+/// Keeps the guide from z-fighting with the ground under the player.
+const GUIDE_LIFT: f32 = 0.1;
+
+pub struct DirectionGuide {
+    pub phase: Arc<PhaseState>,
+    pub direction: TimerDirection,
+    texture: Arc<Texture>,
+    vbuffer: VertexBuffer,
+}
+
+impl DirectionGuide {
+    pub fn new(
+        device: &ID3D11Device,
+        phase: Arc<PhaseState>,
+        direction: TimerDirection,
+        texture: Arc<Texture>,
+    ) -> anyhow::Result<Self> {
+        let vertices = Self::vertices(Vec3::ZERO, direction.destination, 0.0);
+        let vbuffer = Model::from_vertices(vertices.to_vec())
+            .to_buffer(device)
+            .context("Creating direction guide vbuffer")?;
+        Ok(Self {
+            phase,
+            direction,
+            texture,
+            vbuffer,
+        })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.direction
+            .is_active(self.phase.start, tokio::time::Instant::now())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        tokio::time::Instant::now() > self.direction.end(self.phase.start)
+    }
+
+    /**
+     * A single flat strip from the player to the destination; the texture repeats along
+     * its length and is scrolled backwards over time so that it appears to flow forwards.
+     */
+    fn vertices(from: Vec3, to: Vec3, scroll: f32) -> [Vertex; 4] {
+        let from = from + Vec3::Y * GUIDE_LIFT;
+        let to = to + Vec3::Y * GUIDE_LIFT;
+        let path_direction = to - from;
+        let offset = path_direction.cross(Vec3::Y).normalize_or_zero() * GUIDE_WIDTH;
+        let length = path_direction.length() / (GUIDE_WIDTH * 2.0);
+        let vertex = |position: Vec3, texture: Vec2| Vertex {
+            position,
+            colour: Vec3::ONE,
+            normal: Vec3::ZERO,
+            texture,
+        };
+        [
+            vertex(from - offset, Vec2::new(1.0, length - scroll)),
+            vertex(from + offset, Vec2::new(0.0, length - scroll)),
+            vertex(to - offset, Vec2::new(1.0, -scroll)),
+            vertex(to + offset, Vec2::new(0.0, -scroll)),
+        ]
+    }
+
+    /// Draw the guide from the player's position.
+    /// PREREQUISITES: Trail shaders must already be set.
+    pub fn draw(&self, device_context: &ID3D11DeviceContext, from: Vec3) {
+        let scroll = self
+            .direction
+            .scroll(self.phase.start, tokio::time::Instant::now());
+        let vertices = Self::vertices(from, self.direction.destination, scroll);
+        self.texture.set(device_context, 0);
+        unsafe {
+            device_context.UpdateSubresource(
+                &self.vbuffer.buffer,
+                0,
+                None,
+                vertices.as_ptr().cast(),
+                0,
+                0,
+            );
+            device_context.IASetVertexBuffers(
+                0,
+                1,
+                Some(&self.vbuffer.buffer as *const _ as *const _),
+                Some(&self.vbuffer.stride),
+                Some(&self.vbuffer.offset),
+            );
+            device_context.IASetPrimitiveTopology(D3D_PRIMITIVE_TOPOLOGY_TRIANGLESTRIP);
+            device_context.Draw(self.vbuffer.count, 0);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use {
     super::{
+        direction_guide::DirectionGuide,
         dx11::{perspective_input_data::PERSPECTIVEINPUTDATA, InstanceBufferData, RenderBackend},
         object::{ObjectBacking, ObjectLoader},
         pack::Pack,
@@ -9,7 +10,7 @@ use {
         marker::atomic::MarkerInputData,
         space::{
             pack::{loader::DirectoryLoader, trail::ActiveTrail},
-            resources::ObjFile,
+            resources::{ObjFile, Texture},
             max_depth,
         },
         timer::{PhaseState, RotationType, TimerFile, TimerMarker},
@@ -79,6 +80,7 @@ pub struct Engine {
     pub object_kinds: HashMap<String, Arc<ObjectBacking>>,
     phase_states: Vec<Arc<PhaseState>>,
     associated_entities: HashMap<String, Vec<Entity>>,
+    direction_guides: Vec<DirectionGuide>,
 
     schedule: Schedule,
 
@@ -140,6 +142,7 @@ impl Engine {
             schedule,
             world,
             associated_entities: Default::default(),
+            direction_guides: Default::default(),
             phase_states: Default::default(),
             test_pack,
             test_trail,
@@ -197,6 +200,48 @@ impl Engine {
                 entry.push(id);
             }
         }
+        for direction in &phase_state.directions {
+            let (Some(texture), Some(base_path)) = (&direction.texture, &phase_state.timer.path)
+            else {
+                continue;
+            };
+            let texture_path = match base_path.parent() {
+                Some(parent) => texture.to_path(parent),
+                None => texture.to_path(""),
+            };
+            log::info!("Loading direction texture from {texture_path:?}!");
+            // one bad direction shouldn't cost the phase its markers and other directions
+            let texture = match Texture::load(&self.render_backend.device, &texture_path) {
+                Ok(texture) => texture,
+                Err(err) => {
+                    log::error!("Direction texture {texture_path:?} failed to load: {err:?}");
+                    continue;
+                }
+            };
+            let guide = match DirectionGuide::new(
+                &self.render_backend.device,
+                phase_state.clone(),
+                direction.clone(),
+                texture,
+            ) {
+                Ok(guide) => guide,
+                Err(err) => {
+                    log::error!(
+                        "Direction guide \"{}\" creation failed: {err:?}",
+                        direction.name
+                    );
+                    continue;
+                }
+            };
+            log::debug!(
+                "Creating direction guide \"{}\" to {} from timer {}, phase {}",
+                direction.name,
+                direction.destination,
+                phase_state.timer.name(),
+                phase_state.phase.name
+            );
+            self.direction_guides.push(guide);
+        }
         self.phase_states.push(phase_state);
         Ok(())
     }
@@ -207,6 +252,8 @@ impl Engine {
                 self.world.despawn(*entity);
             });
         }
+        self.direction_guides
+            .retain(|g| !Arc::ptr_eq(&g.phase.timer, &timer));
         self.phase_states.retain(|p| !Arc::ptr_eq(&p.timer, &timer));
         Ok(())
     }
@@ -218,6 +265,7 @@ impl Engine {
             }
         }
         self.associated_entities.clear();
+        self.direction_guides.clear();
         self.phase_states.clear();
     }
 
@@ -315,6 +363,14 @@ impl Engine {
                 self.active_test_trail.draw_section(&device_context, i);
             }
         }
+        self.direction_guides.retain(|guide| !guide.is_finished());
+        if self.direction_guides.iter().any(DirectionGuide::is_active) {
+            backend.shaders.0["trail"].set(&device_context);
+            backend.shaders.1["trail"].set(&device_context);
+            for guide in self.direction_guides.iter().filter(|g| g.is_active()) {
+                guide.draw(&device_context, pdata.playpos);
+            }
+        }
         Ok(())
     }
 
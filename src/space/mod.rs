@@ -1,3 +1,4 @@
+pub mod direction_guide;
 pub mod dx11;
 pub mod engine;
 pub mod object;
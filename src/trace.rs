@@ -0,0 +1,206 @@
+use {
+    crate::{
+        controller::ControllerEvent,
+        timer::{CombatState, CombatTriggerEvent},
+        MumbleIdentityUpdate,
+    },
+    async_compression::tokio::{bufread::GzipDecoder, write::GzipEncoder},
+    glam::{Vec2, Vec3},
+    nexus::data_link::{
+        mumble::{MumblePtr, UiState},
+        read_nexus_link,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        fmt,
+        path::{Path, PathBuf},
+    },
+    tokio::{
+        fs::{read_dir, File},
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter},
+        sync::mpsc::Sender,
+        time::{sleep_until, Duration, Instant},
+    },
+};
+
+/// Everything `Controller::mumblelink_tick` reads out of MumbleLink in one go.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MumbleSample {
+    pub position: Vec3,
+    pub camera_front: Vec3,
+    pub camera_position: Vec3,
+    pub ui_state: u32,
+    pub player_map_position: Vec2,
+    pub map_center: Vec2,
+    pub compass_size: Vec2,
+    pub compass_rotation: f32,
+    pub map_scale: f32,
+    #[serde(default)]
+    pub scaling: Option<f32>,
+}
+
+impl MumbleSample {
+    pub fn read(mumble: &MumblePtr) -> Self {
+        let camera = mumble.read_camera();
+        Self {
+            position: Vec3::from_array(mumble.read_avatar().position),
+            camera_front: Vec3::from_array(camera.front),
+            camera_position: Vec3::from_array(camera.position),
+            ui_state: mumble.read_ui_state().bits(),
+            player_map_position: Vec2::from(mumble.read_player_position()),
+            map_center: Vec2::from(mumble.read_map_center()),
+            compass_size: Vec2::new(
+                mumble.read_compass_width() as f32,
+                mumble.read_compass_height() as f32,
+            ),
+            compass_rotation: mumble.read_compass_rotation(),
+            map_scale: mumble.read_map_scale(),
+            scaling: read_nexus_link().map(|link| link.scaling),
+        }
+    }
+
+    pub fn ui_state(&self) -> UiState {
+        UiState::from_bits_truncate(self.ui_state)
+    }
+
+    pub fn in_combat(&self) -> bool {
+        self.ui_state().contains(UiState::IS_IN_COMBAT)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceEvent {
+    Mumble(MumbleSample),
+    Identity(MumbleIdentityUpdate),
+    /// Combat state changes as reported by ArcDPS, rather than MumbleLink.
+    Combat(CombatState),
+    CombatEvent(CombatTriggerEvent),
+    Key { index: u32, released: bool },
+}
+
+/// `taimi-lint simulate` reads these back through [TraceRecord](crate::timer::TraceRecord).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TraceEntry {
+    /// Seconds since the recording began.
+    pub time: f32,
+    #[serde(flatten)]
+    pub event: TraceEvent,
+}
+
+/**
+ * Writes trace entries as gzipped JSON lines, so that a session can be attached to an
+ * issue and replayed later.
+ */
+pub struct TraceRecorder {
+    path: PathBuf,
+    started: Instant,
+    writer: GzipEncoder<BufWriter<File>>,
+}
+
+impl fmt::Debug for TraceRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceRecorder")
+            .field("path", &self.path)
+            .field("started", &self.started)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TraceRecorder {
+    pub const EXTENSION: &str = "jsonl.gz";
+
+    pub async fn create(path: PathBuf) -> anyhow::Result<Self> {
+        let file = File::create(&path).await?;
+        Ok(Self {
+            path,
+            started: Instant::now(),
+            writer: GzipEncoder::new(BufWriter::new(file)),
+        })
+    }
+
+    /// A fresh, timestamped trace path within `dir`.
+    pub fn new_path(dir: &Path) -> PathBuf {
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        dir.join(format!("trace-{stamp}.{}", Self::EXTENSION))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub async fn record(&mut self, event: TraceEvent) -> anyhow::Result<()> {
+        let entry = TraceEntry {
+            time: self.started.elapsed().as_secs_f32(),
+            event,
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        self.writer.write_all(&line).await?;
+        Ok(())
+    }
+
+    pub async fn finish(mut self) -> anyhow::Result<PathBuf> {
+        self.writer.shutdown().await?;
+        Ok(self.path)
+    }
+}
+
+pub struct Trace;
+
+impl Trace {
+    pub async fn load(path: &Path) -> anyhow::Result<Vec<TraceEntry>> {
+        let file = File::open(path).await?;
+        let decoder = GzipDecoder::new(BufReader::new(file));
+        let mut lines = BufReader::new(decoder).lines();
+        let mut entries = Vec::new();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(entries)
+    }
+
+    /// The most recently modified trace within `dir`, if there are any.
+    pub async fn latest(dir: &Path) -> anyhow::Result<Option<PathBuf>> {
+        let mut latest = None;
+        let mut entries = read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_trace = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(TraceRecorder::EXTENSION));
+            if !is_trace {
+                continue;
+            }
+            let modified = entry.metadata().await?.modified()?;
+            if latest.as_ref().is_none_or(|(when, _)| modified > *when) {
+                latest = Some((modified, path));
+            }
+        }
+        Ok(latest.map(|(_, path)| path))
+    }
+
+    /**
+     * Feeds the entries back to the controller at the pace they were recorded,
+     * finishing with a [ControllerEvent::TraceReplayFinished].
+     */
+    pub async fn replay(entries: Vec<TraceEntry>, sender: Sender<ControllerEvent>) {
+        let started = Instant::now();
+        for entry in entries {
+            sleep_until(started + Duration::from_secs_f32(entry.time.max(0.0))).await;
+            if sender
+                .send(ControllerEvent::TraceReplayEvent(entry.event))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+        let _ = sender.send(ControllerEvent::TraceReplayFinished).await;
+    }
+}
@@ -0,0 +1,115 @@
+use {
+    notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher},
+    std::{
+        collections::{HashMap, HashSet},
+        path::PathBuf,
+    },
+    tokio::{
+        sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        time::{Duration, Instant},
+    },
+};
+
+/// How long a file has to be left alone before it is reloaded, as editors often save in several writes.
+const SETTLE_TIME: Duration = Duration::from_millis(300);
+
+/**
+ * Watches the timer and marker directories, collecting the files that change in them.
+ *
+ * Changes are only handed out once a file has settled, so that a half-written file is not
+ * loaded and a burst of writes only causes a single reload.
+ */
+pub struct DataWatcher {
+    watcher: Option<RecommendedWatcher>,
+    // kept so that recv() waits rather than finishing when the watcher couldn't be created
+    _sender: UnboundedSender<notify::Result<Event>>,
+    receiver: UnboundedReceiver<notify::Result<Event>>,
+    watched: HashSet<PathBuf>,
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl DataWatcher {
+    pub fn new() -> Self {
+        let (sender, receiver) = unbounded_channel();
+        let event_sender = sender.clone();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = event_sender.send(event);
+        });
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                log::error!("Files will not be reloaded when they change: {err}");
+                None
+            }
+        };
+        Self {
+            watcher,
+            _sender: sender,
+            receiver,
+            watched: Default::default(),
+            pending: Default::default(),
+        }
+    }
+
+    /// Watches exactly `dirs`, leaving alone those already being watched.
+    pub fn watch(&mut self, dirs: HashSet<PathBuf>) {
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+        for dir in self.watched.difference(&dirs) {
+            log::debug!("No longer watching {dir:?} for changes.");
+            if let Err(err) = watcher.unwatch(dir) {
+                log::warn!("Couldn't stop watching {dir:?}: {err}");
+            }
+        }
+        self.watched.retain(|dir| dirs.contains(dir));
+        for dir in dirs {
+            if self.watched.contains(&dir) || !dir.is_dir() {
+                continue;
+            }
+            match watcher.watch(&dir, RecursiveMode::Recursive) {
+                Ok(()) => {
+                    log::debug!("Watching {dir:?} for changes.");
+                    self.watched.insert(dir);
+                }
+                Err(err) => log::warn!("Couldn't watch {dir:?} for changes: {err}"),
+            }
+        }
+    }
+
+    /// Waits for the next filesystem event, noting down the files it touched.
+    pub async fn recv(&mut self) {
+        let Some(event) = self.receiver.recv().await else {
+            return;
+        };
+        match event {
+            Ok(event) => {
+                if matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    let now = Instant::now();
+                    for path in event.paths {
+                        self.pending.insert(path, now);
+                    }
+                }
+            }
+            Err(err) => log::warn!("File watcher error: {err}"),
+        }
+    }
+
+    /// The files that have changed and since been left alone for long enough.
+    pub fn settled(&mut self) -> Vec<PathBuf> {
+        let now = Instant::now();
+        let settled: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, changed)| now.duration_since(**changed) >= SETTLE_TIME)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &settled {
+            self.pending.remove(path);
+        }
+        settled
+    }
+}
@@ -0,0 +1,315 @@
+use {
+    std::{
+        collections::HashMap,
+        fmt,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+    taimi_timer::{TimerFile, TimerPhase, TimerTrigger, TimerTriggerType},
+};
+
+/// A problem with one timer file.
+pub struct Diagnostic {
+    pub path: Option<PathBuf>,
+    pub timer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display())?,
+            None => write!(f, "<unknown>")?,
+        }
+        write!(f, ": {}: {}", self.timer, self.message)
+    }
+}
+
+struct TimerLinter<'a> {
+    timer: &'a TimerFile,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> TimerLinter<'a> {
+    fn new(timer: &'a TimerFile) -> Self {
+        Self {
+            timer,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, message: String) {
+        self.diagnostics.push(Diagnostic {
+            path: self.timer.path.clone(),
+            timer: self.timer.id.clone(),
+            message,
+        });
+    }
+
+    /// Textures are looked up next to the timer file, the same way the addon loads them.
+    fn check_file(&mut self, location: &str, kind: &str, relative: &str) {
+        if relative.is_empty() {
+            return;
+        }
+        let Some(base) = self.timer.path.as_deref().and_then(Path::parent) else {
+            return;
+        };
+        let path = relative_path::RelativePath::new(relative).to_path(base);
+        if !path.is_file() {
+            self.report(format!("{location}: {kind} {relative:?} does not exist"));
+        }
+    }
+
+    fn check_trigger(&mut self, location: &str, trigger: &TimerTrigger) {
+        use TimerTriggerType::*;
+        if trigger.kind == Key && trigger.key_flag().is_none() {
+            match &trigger.key_bind {
+                Some(key_bind) => self.report(format!(
+                    "{location}: key trigger has an invalid key bind {key_bind:?}"
+                )),
+                None => self.report(format!("{location}: key trigger has no key bind")),
+            }
+        }
+        if trigger.needs_shape() && trigger.polytope().is_none() {
+            self.report(format!("{location}: {} trigger has no shape", trigger.kind));
+        }
+        if matches!(trigger.kind, All | Any | Not) && trigger.triggers.is_empty() {
+            self.report(format!(
+                "{location}: {} trigger has no triggers",
+                trigger.kind
+            ));
+        }
+        for (index, sub_trigger) in trigger.triggers.iter().enumerate() {
+            self.check_trigger(&format!("{location} > trigger {index}"), sub_trigger);
+        }
+    }
+
+    fn check_phase(&mut self, phase: &TimerPhase) {
+        let location = format!("phase {:?}", phase.name);
+        let is_empty = phase.alerts.is_empty()
+            && phase.actions.is_empty()
+            && phase.directions.is_empty()
+            && phase.markers.0.is_empty()
+            && phase.sounds.is_empty();
+        if is_empty {
+            self.report(format!("{location}: phase has nothing to show"));
+        }
+        self.check_trigger(&format!("{location} > start"), &phase.start);
+        if let Some(finish) = &phase.finish {
+            self.check_trigger(&format!("{location} > finish"), finish);
+        }
+        for (index, alert) in phase.alerts.iter().enumerate() {
+            let location = format!("{location} > alert {index}");
+            let no_duration = alert.alert_duration.is_none() && alert.warning_duration.is_none();
+            if !alert.timestamps.is_empty() && no_duration {
+                self.report(format!(
+                    "{location}: alert has timestamps but no alert or warning duration"
+                ));
+            }
+            if let Some(icon) = &alert.icon {
+                self.check_file(&location, "icon", icon.as_str());
+            }
+        }
+        for (index, marker) in phase.markers.0.iter().enumerate() {
+            let texture = marker.texture.to_string_lossy();
+            self.check_file(&format!("{location} > marker {index}"), "texture", &texture);
+        }
+        for (index, direction) in phase.directions.iter().enumerate() {
+            if let Some(texture) = &direction.texture {
+                let location = format!("{location} > direction {index}");
+                self.check_file(&location, "texture", texture.as_str());
+            }
+        }
+    }
+
+    fn lint(mut self) -> Vec<Diagnostic> {
+        let timer = self.timer;
+        self.check_file("timer", "icon", timer.icon.as_str());
+        self.check_trigger("reset", &timer.reset);
+        if timer.phases.is_empty() {
+            self.report("timer has no phases".to_string());
+        }
+        for phase in &timer.phases {
+            self.check_phase(phase);
+        }
+        self.diagnostics
+    }
+}
+
+/// Checks each timer on its own, then that no two of them share an ID.
+pub fn lint_timers(timers: &[Arc<TimerFile>]) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = timers
+        .iter()
+        .flat_map(|timer| TimerLinter::new(timer).lint())
+        .collect();
+
+    let mut by_id: HashMap<&str, Vec<&TimerFile>> = HashMap::new();
+    for timer in timers {
+        by_id.entry(&timer.id).or_default().push(timer);
+    }
+    for (id, mut timers) in by_id {
+        timers.sort_by(|a, b| a.path.cmp(&b.path));
+        let Some((first, duplicates)) = timers.split_first() else {
+            continue;
+        };
+        for duplicate in duplicates {
+            let first_path = first
+                .path
+                .as_ref()
+                .map_or_else(|| "<unknown>".to_string(), |p| p.display().to_string());
+            diagnostics.push(Diagnostic {
+                path: duplicate.path.clone(),
+                timer: id.to_string(),
+                message: format!("timer ID is already used by {first_path}"),
+            });
+        }
+    }
+
+    diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::{json, Value},
+    };
+
+    fn trigger() -> Value {
+        json!({ "type": "location", "position": [0.0, 0.0, 0.0], "radius": 10.0 })
+    }
+
+    fn timer_json() -> Value {
+        json!({
+            "id": "test",
+            "name": "Test",
+            "category": "Tests",
+            "description": "",
+            "author": "",
+            "icon": "",
+            "map": 1,
+            "reset": trigger(),
+            "phases": [{
+                "name": "Start",
+                "start": trigger(),
+                "alerts": [{ "alert": "Go", "alertDuration": 5.0, "timestamps": [1.0] }],
+            }],
+        })
+    }
+
+    fn timer(value: Value) -> Arc<TimerFile> {
+        Arc::new(serde_json::from_value(value).expect("test timer should deserialize"))
+    }
+
+    fn messages(value: Value) -> Vec<String> {
+        lint_timers(&[timer(value)])
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn clean_timer_has_no_diagnostics() {
+        assert!(messages(timer_json()).is_empty());
+    }
+
+    #[test]
+    fn key_trigger_needs_a_valid_bind() {
+        let mut value = timer_json();
+        value["phases"][0]["start"]["type"] = json!("key");
+        assert_eq!(
+            messages(value.clone()),
+            ["phase \"Start\" > start: key trigger has no key bind"]
+        );
+        value["phases"][0]["start"]["keyBind"] = json!("9");
+        assert_eq!(
+            messages(value.clone()),
+            ["phase \"Start\" > start: key trigger has an invalid key bind \"9\""]
+        );
+        value["phases"][0]["start"]["keyBind"] = json!("2");
+        assert!(messages(value).is_empty());
+    }
+
+    #[test]
+    fn location_trigger_needs_a_shape() {
+        let mut value = timer_json();
+        value["reset"] = json!({ "type": "location" });
+        assert_eq!(messages(value), ["reset: location trigger has no shape"]);
+    }
+
+    #[test]
+    fn group_trigger_needs_triggers() {
+        let mut value = timer_json();
+        value["phases"][0]["finish"] = json!({ "type": "any" });
+        assert_eq!(
+            messages(value.clone()),
+            ["phase \"Start\" > finish: any trigger has no triggers"]
+        );
+        value["phases"][0]["finish"] =
+            json!({ "type": "not", "triggers": [{ "type": "location" }] });
+        assert_eq!(
+            messages(value),
+            ["phase \"Start\" > finish > trigger 0: location trigger has no shape"]
+        );
+    }
+
+    #[test]
+    fn empty_phase_is_reported() {
+        let mut value = timer_json();
+        value["phases"][0]["alerts"] = json!([]);
+        assert_eq!(
+            messages(value),
+            ["phase \"Start\": phase has nothing to show"]
+        );
+    }
+
+    #[test]
+    fn timestamps_need_a_duration() {
+        let mut value = timer_json();
+        value["phases"][0]["alerts"] = json!([{ "alert": "Go", "timestamps": [1.0] }]);
+        assert_eq!(
+            messages(value),
+            ["phase \"Start\" > alert 0: alert has timestamps but no alert or warning duration"]
+        );
+    }
+
+    #[test]
+    fn missing_icon_is_reported() {
+        let dir = std::env::temp_dir().join(format!("taimi-lint-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("present.png"), []).unwrap();
+        let mut value = timer_json();
+        value["path"] = json!(dir.join("test.bhtimer"));
+        value["icon"] = json!("present.png");
+        value["phases"][0]["alerts"][0]["icon"] = json!("missing.png");
+        let result = messages(value);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            result,
+            ["phase \"Start\" > alert 0: icon \"missing.png\" does not exist"]
+        );
+    }
+
+    #[test]
+    fn timer_without_phases_is_reported() {
+        let mut value = timer_json();
+        value["phases"] = json!([]);
+        assert_eq!(messages(value), ["timer has no phases"]);
+    }
+
+    #[test]
+    fn duplicate_ids_are_reported_against_the_first_file() {
+        let mut first = timer_json();
+        first["path"] = json!("a.bhtimer");
+        let mut second = timer_json();
+        second["path"] = json!("b.bhtimer");
+        let diagnostics = lint_timers(&[timer(second), timer(first)]);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].path, Some(PathBuf::from("b.bhtimer")));
+        assert_eq!(
+            diagnostics[0].message,
+            "timer ID is already used by a.bhtimer"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Checks a directory of `.bhtimer` files for the problems that would otherwise only show
+//! up in game, if at all, and replays recorded traces against a timer.
+
+mod lint;
+mod simulate;
+
+use {
+    lint::Diagnostic,
+    log::{Level, LevelFilter, Log, Metadata, Record},
+    std::{
+        path::{Path, PathBuf},
+        process::ExitCode,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+    taimi_timer::TimerFile,
+};
+
+/// Prints the loader's warnings and errors, counting the errors as files that failed to load.
+struct LintLogger {
+    errors: AtomicUsize,
+}
+
+impl Log for LintLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if record.level() == Level::Error {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        eprintln!(
+            "{}: {}",
+            record.level().as_str().to_lowercase(),
+            record.args()
+        );
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: LintLogger = LintLogger {
+    errors: AtomicUsize::new(0),
+};
+
+fn usage() -> ExitCode {
+    eprintln!("usage: taimi-lint <timer directory>...");
+    eprintln!("       taimi-lint simulate <timer file> <trace file>");
+    ExitCode::from(2)
+}
+
+enum Command {
+    Lint(Vec<PathBuf>),
+    Simulate { timer: PathBuf, trace: PathBuf },
+}
+
+impl Command {
+    fn parse() -> Option<Self> {
+        let mut args = std::env::args_os().skip(1).map(PathBuf::from);
+        let first = args.next()?;
+        if first.as_os_str() == "simulate" {
+            let (Some(timer), Some(trace), None) = (args.next(), args.next(), args.next()) else {
+                return None;
+            };
+            return Some(Self::Simulate { timer, trace });
+        }
+        Some(Self::Lint(std::iter::once(first).chain(args).collect()))
+    }
+}
+
+async fn lint(dirs: Vec<PathBuf>) -> anyhow::Result<(usize, Vec<Diagnostic>)> {
+    let mut timers = Vec::new();
+    for dir in &dirs {
+        if !dir.is_dir() {
+            anyhow::bail!("{dir:?} is not a directory");
+        }
+        timers.extend(TimerFile::load_many_sourceless(dir, 64).await?);
+    }
+    Ok((timers.len(), lint::lint_timers(&timers)))
+}
+
+fn main() -> ExitCode {
+    let Some(command) = Command::parse() else {
+        return usage();
+    };
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Warn);
+    }
+
+    let rt = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(err) => {
+            eprintln!("error: could not start the runtime: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match command {
+        Command::Lint(dirs) => run_lint(&rt, dirs),
+        Command::Simulate { timer, trace } => run_simulate(&rt, &timer, &trace),
+    }
+}
+
+/// Prints the timeline as JSON lines, so that runs can be compared with `diff`.
+fn run_simulate(rt: &tokio::runtime::Runtime, timer: &PathBuf, trace: &Path) -> ExitCode {
+    let timeline = match rt.block_on(simulate::simulate(timer, trace)) {
+        Ok(timeline) => timeline,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            return ExitCode::FAILURE;
+        }
+    };
+    for entry in &timeline {
+        match serde_json::to_string(entry) {
+            Ok(line) => println!("{line}"),
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_lint(rt: &tokio::runtime::Runtime, dirs: Vec<PathBuf>) -> ExitCode {
+    let (loaded, diagnostics) = match rt.block_on(lint(dirs)) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for diagnostic in &diagnostics {
+        println!("{diagnostic}");
+    }
+    let load_errors = LOGGER.errors.load(Ordering::Relaxed);
+    eprintln!(
+        "{loaded} timers checked, {} problems found, {load_errors} files failed to load.",
+        diagnostics.len()
+    );
+    if diagnostics.is_empty() && load_errors == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
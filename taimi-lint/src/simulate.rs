@@ -0,0 +1,150 @@
+use {
+    anyhow::Context,
+    flate2::bufread::MultiGzDecoder,
+    std::{
+        fs::File,
+        io::{BufRead, BufReader},
+        path::{Path, PathBuf},
+    },
+    taimi_timer::{Simulation, SimulationSample, TimelineEntry, TimerFile, TraceRecord},
+};
+
+/// Reads a trace recorded by the addon, which is gzipped unless its name says otherwise.
+fn read_trace(path: &Path) -> anyhow::Result<Vec<TraceRecord>> {
+    let file = BufReader::new(File::open(path)?);
+    let gzipped = path.extension().is_some_and(|extension| extension == "gz");
+    match gzipped {
+        true => parse_trace(BufReader::new(MultiGzDecoder::new(file))),
+        false => parse_trace(file),
+    }
+}
+
+fn parse_trace(reader: impl BufRead) -> anyhow::Result<Vec<TraceRecord>> {
+    let mut records = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .with_context(|| format!("line {} of the trace", index + 1))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Replays a recorded trace against one timer file, without the game.
+pub async fn simulate(timer: &PathBuf, trace: &Path) -> anyhow::Result<Vec<TimelineEntry>> {
+    let timer = TimerFile::load(timer, None).await?;
+    let records = read_trace(trace).with_context(|| format!("Couldn't read trace {trace:?}"))?;
+    let samples = SimulationSample::from_trace(&records);
+    Ok(Simulation::run(timer, &samples).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        flate2::{write::GzEncoder, Compression},
+        serde_json::json,
+        std::io::Write,
+        taimi_timer::{TimelineEvent, TimerAlertType},
+    };
+
+    fn location(x: f32, radius: f32) -> serde_json::Value {
+        json!({
+            "type": "location",
+            "position": [x, 0.0, 0.0],
+            "radius": radius,
+            "requireEntry": true,
+        })
+    }
+
+    fn mumble(time: f32, x: f32) -> serde_json::Value {
+        json!({
+            "time": time,
+            "mumble": { "position": [x, 0.0, 0.0], "cameraFront": [0.0, 0.0, 1.0], "uiState": 8 },
+        })
+    }
+
+    #[tokio::test]
+    async fn skip_time_is_recorded_as_a_skip() {
+        let dir = std::env::temp_dir().join(format!("taimi-simulate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let timer_path = dir.join("skip.bhtimer");
+        let timer = json!({
+            "id": "skip",
+            "name": "Skip",
+            "category": "Tests",
+            "description": "",
+            "author": "",
+            "icon": "",
+            "map": 1,
+            "reset": location(1000.0, 1.0),
+            "phases": [{
+                "name": "Start",
+                "start": location(0.0, 10.0),
+                "alerts": [{ "alert": "Soon", "alertDuration": 5.0, "timestamps": [20.0] }],
+                "actions": [{
+                    "name": "Ahead",
+                    "type": "skipTime",
+                    "time": 5.0,
+                    "trigger": location(100.0, 10.0),
+                }],
+            }],
+        });
+        std::fs::write(&timer_path, timer.to_string()).unwrap();
+
+        let trace_path = dir.join("trace.jsonl.gz");
+        let mut trace = GzEncoder::new(File::create(&trace_path).unwrap(), Compression::default());
+        let lines = [
+            json!({ "time": 0.0, "identity": { "name": "Taimi", "map_id": 1 } }),
+            mumble(0.5, 0.0),
+            mumble(1.0, 100.0),
+            mumble(7.0, 100.0),
+            mumble(12.0, 100.0),
+            mumble(16.0, 100.0),
+        ];
+        for line in lines {
+            writeln!(trace, "{line}").unwrap();
+        }
+        trace.finish().unwrap();
+
+        let timeline = simulate(&timer_path, &trace_path).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+        let timeline: Vec<_> = timeline
+            .unwrap()
+            .into_iter()
+            .map(|entry| (format!("{:.1}", entry.time), entry.event))
+            .collect();
+
+        let soon = |time: &str, started: bool| {
+            let (kind, text) = (TimerAlertType::Alert, "Soon".to_string());
+            let event = match started {
+                true => TimelineEvent::AlertStarted { kind, text },
+                false => TimelineEvent::AlertEnded { kind, text },
+            };
+            (time.to_string(), event)
+        };
+        assert_eq!(
+            timeline,
+            [
+                (
+                    "0.5".to_string(),
+                    TimelineEvent::PhaseStarted {
+                        phase: "Start".to_string()
+                    }
+                ),
+                (
+                    "1.0".to_string(),
+                    TimelineEvent::TimeSkipped {
+                        phase: "Start".to_string(),
+                        seconds: 5.0
+                    }
+                ),
+                soon("10.5", true),
+                soon("15.5", false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use {
+    crate::TimerTrigger,
+    serde::{Deserialize, Serialize},
+};
+
+/**
+ * Actions are evaluated while their phase is running, and each one fires at most once
+ * per run of that phase. One that restarts its own phase won't fire in the new run until
+ * its trigger has stopped holding.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerAction {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: TimerActionType,
+    /// Restricts a time skip to the alerts, markers, directions and sounds in these sets.
+    pub sets: Option<Vec<String>>,
+    /// Seconds to skip forward by, for [TimerActionType::SkipTime].
+    #[serde(default)]
+    pub time: Option<f32>,
+    /// Name of the phase to jump to, for [TimerActionType::JumpToPhase].
+    #[serde(default)]
+    pub phase: Option<String>,
+    pub trigger: TimerTrigger,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum TimerActionType {
+    #[default]
+    SkipTime,
+    JumpToPhase,
+    RestartPhase,
+}
@@ -1,12 +1,14 @@
 use {
-    crate::timer::BlishColour,
+    crate::{
+        AlertOverrides, AlertPlayer, AlertVariables, BlishColour, PhaseState, RemainingFormat,
+    },
     relative_path::RelativePathBuf,
     serde::{Deserialize, Serialize},
     strum_macros::Display,
     tokio::time::{Duration, Instant},
 };
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct BlishAlert {
     #[serde(default)]
@@ -27,9 +29,11 @@ pub struct BlishAlert {
     pub fill_color: Option<BlishColour>,
     #[serde(default)]
     pub timestamps: Vec<f32>,
+    #[serde(default)]
+    pub set: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Display, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Display, Copy, PartialEq)]
 pub enum TimerAlertType {
     Alert,
     Warning,
@@ -45,6 +49,7 @@ impl BlishAlert {
             fill_colour: self.fill_color,
             timestamp,
             icon: self.icon.clone(),
+            set: self.set.clone(),
         })
     }
 
@@ -57,17 +62,25 @@ impl BlishAlert {
             fill_colour: self.fill_color,
             timestamp,
             icon: self.icon.clone(),
+            set: self.set.clone(),
         })
     }
 
-    pub fn get_alerts(&self) -> Vec<TimerAlert> {
+    /// The alerts at each timestamp, moved by `offset` seconds and changed by `overrides`.
+    pub fn get_alerts(&self, offset: f32, overrides: Option<&AlertOverrides>) -> Vec<TimerAlert> {
+        let offset = offset + overrides.map_or(0.0, |overrides| overrides.offset);
         self.timestamps
             .iter()
-            .flat_map(|&timestamp| {
+            .map(|&timestamp| (timestamp + offset).max(0.0))
+            .flat_map(|timestamp| {
                 self.alert(timestamp)
                     .into_iter()
                     .chain(self.warning(timestamp))
             })
+            .filter_map(|alert| match overrides {
+                Some(overrides) => overrides.apply(alert),
+                None => Some(alert),
+            })
             .collect()
     }
 }
@@ -81,6 +94,7 @@ pub struct TimerAlert {
     pub icon: Option<RelativePathBuf>,
     pub timestamp: f32,
     pub duration: f32,
+    pub set: Option<String>,
 }
 
 impl TimerAlert {
@@ -101,8 +115,8 @@ impl TimerAlert {
     pub fn start(&self, start: Instant) -> Instant {
         start + self.timestamp()
     }
-    pub fn percentage(&self, start: Instant) -> Option<f32> {
-        let elapsed = Instant::now()
+    pub fn percentage(&self, start: Instant, now: Instant) -> Option<f32> {
+        let elapsed = now
             .checked_duration_since(self.start(start))?
             .as_secs_f32();
         if elapsed > self.duration {
@@ -111,14 +125,35 @@ impl TimerAlert {
             Some(elapsed / self.duration)
         }
     }
-    pub fn remaining(&self, start: Instant) -> Duration {
-        self.end(start).saturating_duration_since(Instant::now())
+    pub fn remaining(&self, start: Instant, now: Instant) -> Duration {
+        self.end(start).saturating_duration_since(now)
     }
-    pub fn progress_bar_text(&self, start: Instant) -> String {
-        format!(
-            "{} - in {:.1}s",
-            self.text,
-            self.remaining(start).as_secs_f32()
-        )
+    /**
+     * The alert's text with its placeholders filled in, followed by the time remaining
+     * unless the text already places that itself.
+     */
+    pub fn progress_bar_text(
+        &self,
+        ps: &PhaseState,
+        now: Instant,
+        remaining_format: RemainingFormat,
+        player: &AlertPlayer,
+    ) -> String {
+        let remaining = self.remaining(ps.start, now);
+        let variables = AlertVariables {
+            remaining,
+            remaining_format,
+            phase: &ps.phase.name,
+            timer: &ps.timer.name,
+            player,
+        };
+        let text = variables.render(&self.text);
+        if remaining_format == RemainingFormat::Hidden
+            || AlertVariables::mentions_remaining(&self.text)
+        {
+            text
+        } else {
+            format!("{} - in {}", text, remaining_format.format(remaining))
+        }
     }
 }
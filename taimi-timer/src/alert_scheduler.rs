@@ -0,0 +1,80 @@
+use {
+    serde::{Deserialize, Serialize},
+    strum_macros::{Display, EnumIter},
+    tokio::time::{Duration, Instant},
+};
+
+/// How urgently a text alert wants to be on screen, should too many be due at once.
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Display,
+    EnumIter,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum TextAlertPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical,
+}
+
+#[derive(Debug, Clone)]
+pub struct QueuedAlert {
+    pub message: String,
+    pub show_at: Instant,
+    pub duration: Duration,
+    pub priority: TextAlertPriority,
+}
+
+/**
+ * The text alerts a single timer has coming up.
+ *
+ * Rather than sleeping in tasks of their own, alerts are polled from the timer's tick,
+ * so that cancelling them is just a matter of forgetting about them. Once due, an alert
+ * is handed to the renderer, which decides what fits on screen alongside other timers.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct AlertScheduler {
+    pending: Vec<QueuedAlert>,
+    shown: bool,
+}
+
+impl AlertScheduler {
+    pub fn schedule(&mut self, alert: QueuedAlert) {
+        let idx = self
+            .pending
+            .partition_point(|pending| pending.show_at <= alert.show_at);
+        self.pending.insert(idx, alert);
+    }
+
+    pub fn pending(&self) -> &[QueuedAlert] {
+        &self.pending
+    }
+
+    /// Forgets every pending alert, returning whether any had already been shown.
+    pub fn cancel(&mut self) -> bool {
+        self.pending.clear();
+        std::mem::take(&mut self.shown)
+    }
+
+    /// The alerts that have come due by `now`, in the order they were due.
+    pub fn poll(&mut self, now: Instant) -> Vec<QueuedAlert> {
+        let due = self.pending.partition_point(|alert| alert.show_at <= now);
+        let due: Vec<_> = self.pending.drain(..due).collect();
+        if !due.is_empty() {
+            self.shown = true;
+        }
+        due
+    }
+}
@@ -0,0 +1,353 @@
+use {
+    chrono::{DateTime, Utc},
+    serde::{Deserialize, Serialize},
+    std::{collections::HashMap, path::Path},
+    tokio::{
+        fs::{read_to_string, rename, write},
+        time::Instant,
+    },
+};
+
+/// How many attempts are kept for each timer, dropping the oldest first.
+const MAX_ATTEMPTS_PER_TIMER: usize = 500;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AttemptOutcome {
+    Finished,
+    Reset,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PhaseTime {
+    pub name: String,
+    pub seconds: f32,
+    /// Whether the phase was completed, rather than being cut short by a reset.
+    pub finished: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Attempt {
+    pub timer: String,
+    pub started: DateTime<Utc>,
+    pub phases: Vec<PhaseTime>,
+    pub outcome: AttemptOutcome,
+    #[serde(default)]
+    pub character: Option<String>,
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+impl Attempt {
+    pub fn seconds(&self) -> f32 {
+        self.phases.iter().map(|phase| phase.seconds).sum()
+    }
+}
+
+/**
+ * Follows a timer machine from its first phase starting until it either finishes or is
+ * reset, keeping what happened until the controller collects it.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct AttemptRecorder {
+    started: Option<DateTime<Utc>>,
+    phases: Vec<PhaseTime>,
+    current_phase: Option<(String, Instant)>,
+    recorded: Vec<Attempt>,
+}
+
+impl AttemptRecorder {
+    /// A phase restarting, or being jumped to, starts its clock over.
+    pub fn phase_started(&mut self, name: &str, now: Instant) {
+        self.started.get_or_insert_with(Utc::now);
+        self.current_phase = Some((name.to_string(), now));
+    }
+
+    pub fn phase_finished(&mut self, now: Instant) {
+        if let Some((name, start)) = self.current_phase.take() {
+            self.phases.push(PhaseTime {
+                name,
+                seconds: now.saturating_duration_since(start).as_secs_f32(),
+                finished: true,
+            });
+        }
+    }
+
+    pub fn end(&mut self, timer: &str, outcome: AttemptOutcome, now: Instant) {
+        if let Some((name, start)) = self.current_phase.take() {
+            self.phases.push(PhaseTime {
+                name,
+                seconds: now.saturating_duration_since(start).as_secs_f32(),
+                finished: outcome == AttemptOutcome::Finished,
+            });
+        }
+        let Some(started) = self.started.take() else {
+            return;
+        };
+        self.recorded.push(Attempt {
+            timer: timer.to_string(),
+            started,
+            phases: std::mem::take(&mut self.phases),
+            outcome,
+            character: None,
+            account: None,
+        });
+    }
+
+    /// Forgets the attempt in progress, for when the machine is going away mid-fight.
+    pub fn abandon(&mut self) {
+        self.started = None;
+        self.phases.clear();
+        self.current_phase = None;
+    }
+
+    pub fn take(&mut self) -> Vec<Attempt> {
+        std::mem::take(&mut self.recorded)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PhaseStats {
+    pub name: String,
+    pub best: Option<f32>,
+    pub median: Option<f32>,
+    /// How many attempts were reset during this phase.
+    pub resets: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AttemptStats {
+    pub attempts: usize,
+    pub finished: usize,
+    pub best: Option<f32>,
+    pub median: Option<f32>,
+    pub phases: Vec<PhaseStats>,
+}
+
+fn best_and_median(mut seconds: Vec<f32>) -> (Option<f32>, Option<f32>) {
+    if seconds.is_empty() {
+        return (None, None);
+    }
+    seconds.sort_by(f32::total_cmp);
+    let middle = seconds.len() / 2;
+    let median = match seconds.len() % 2 {
+        0 => (seconds[middle - 1] + seconds[middle]) / 2.0,
+        _ => seconds[middle],
+    };
+    (seconds.first().copied(), Some(median))
+}
+
+impl AttemptStats {
+    pub fn from_attempts(attempts: &[Attempt]) -> Self {
+        let finished: Vec<&Attempt> = attempts
+            .iter()
+            .filter(|attempt| attempt.outcome == AttemptOutcome::Finished)
+            .collect();
+        let (best, median) = best_and_median(finished.iter().map(|a| a.seconds()).collect());
+
+        let mut phases: Vec<(PhaseStats, Vec<f32>)> = Vec::new();
+        for phase in attempts.iter().flat_map(|attempt| &attempt.phases) {
+            let index = match phases
+                .iter()
+                .position(|(stats, _)| stats.name == phase.name)
+            {
+                Some(index) => index,
+                None => {
+                    let stats = PhaseStats {
+                        name: phase.name.clone(),
+                        ..Default::default()
+                    };
+                    phases.push((stats, Vec::new()));
+                    phases.len() - 1
+                }
+            };
+            let (stats, seconds) = &mut phases[index];
+            match phase.finished {
+                true => seconds.push(phase.seconds),
+                false => stats.resets += 1,
+            }
+        }
+        let phases = phases
+            .into_iter()
+            .map(|(mut stats, seconds)| {
+                (stats.best, stats.median) = best_and_median(seconds);
+                stats
+            })
+            .collect();
+
+        Self {
+            attempts: attempts.len(),
+            finished: finished.len(),
+            best,
+            median,
+            phases,
+        }
+    }
+
+    pub fn success_rate(&self) -> f32 {
+        match self.attempts {
+            0 => 0.0,
+            attempts => self.finished as f32 / attempts as f32,
+        }
+    }
+}
+
+/// Every recorded attempt, by timer ID, kept in the addon directory.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AttemptHistory {
+    #[serde(default)]
+    pub timers: HashMap<String, Vec<Attempt>>,
+}
+
+impl AttemptHistory {
+    const FILENAME: &str = "history.json";
+
+    pub async fn load(addon_dir: &Path) -> anyhow::Result<Self> {
+        let path = addon_dir.join(Self::FILENAME);
+        if !path.exists() {
+            return Ok(Default::default());
+        }
+        let file_data = read_to_string(&path).await?;
+        Ok(serde_json::from_str(&file_data)?)
+    }
+
+    /// Written alongside and then moved into place, so a crash mid-write can't lose the history.
+    pub async fn save(&self, addon_dir: &Path) -> anyhow::Result<()> {
+        let path = addon_dir.join(Self::FILENAME);
+        let partial = path.with_extension("json.tmp");
+        write(&partial, serde_json::to_string(self)?).await?;
+        rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, attempt: Attempt) {
+        let attempts = self.timers.entry(attempt.timer.clone()).or_default();
+        attempts.push(attempt);
+        let excess = attempts.len().saturating_sub(MAX_ATTEMPTS_PER_TIMER);
+        attempts.drain(..excess);
+    }
+
+    pub fn clear(&mut self, timer: &str) {
+        self.timers.remove(timer);
+    }
+
+    pub fn stats(&self) -> HashMap<String, AttemptStats> {
+        self.timers
+            .iter()
+            .map(|(timer, attempts)| (timer.clone(), AttemptStats::from_attempts(attempts)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attempt(outcome: AttemptOutcome, phases: &[(&str, f32, bool)]) -> Attempt {
+        Attempt {
+            timer: "test".to_string(),
+            started: Utc::now(),
+            phases: phases
+                .iter()
+                .map(|&(name, seconds, finished)| PhaseTime {
+                    name: name.to_string(),
+                    seconds,
+                    finished,
+                })
+                .collect(),
+            outcome,
+            character: None,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn best_and_median_of_nothing() {
+        assert_eq!(best_and_median(Vec::new()), (None, None));
+    }
+
+    #[test]
+    fn best_and_median_of_an_odd_count() {
+        assert_eq!(
+            best_and_median(vec![30.0, 10.0, 20.0]),
+            (Some(10.0), Some(20.0))
+        );
+    }
+
+    #[test]
+    fn best_and_median_of_an_even_count() {
+        assert_eq!(
+            best_and_median(vec![40.0, 10.0, 30.0, 20.0]),
+            (Some(10.0), Some(25.0))
+        );
+    }
+
+    #[test]
+    fn stats_of_no_attempts() {
+        let stats = AttemptStats::from_attempts(&[]);
+        assert_eq!((stats.attempts, stats.finished), (0, 0));
+        assert_eq!((stats.best, stats.median), (None, None));
+        assert!(stats.phases.is_empty());
+        assert_eq!(stats.success_rate(), 0.0);
+    }
+
+    #[test]
+    fn stats_only_time_finished_attempts_and_phases() {
+        use AttemptOutcome::*;
+        let attempts = [
+            attempt(Finished, &[("Split", 10.0, true), ("Burn", 20.0, true)]),
+            attempt(Finished, &[("Split", 14.0, true), ("Burn", 26.0, true)]),
+            attempt(Reset, &[("Split", 5.0, true), ("Burn", 2.0, false)]),
+            attempt(Reset, &[("Split", 1.0, false)]),
+        ];
+        let stats = AttemptStats::from_attempts(&attempts);
+        assert_eq!((stats.attempts, stats.finished), (4, 2));
+        assert_eq!((stats.best, stats.median), (Some(30.0), Some(35.0)));
+        assert_eq!(stats.success_rate(), 0.5);
+
+        let phases: Vec<_> = stats
+            .phases
+            .iter()
+            .map(|phase| (phase.name.as_str(), phase.best, phase.median, phase.resets))
+            .collect();
+        assert_eq!(
+            phases,
+            [
+                ("Split", Some(5.0), Some(10.0), 1),
+                ("Burn", Some(20.0), Some(23.0), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn phases_that_were_only_ever_reset_have_no_times() {
+        let attempts = [
+            attempt(AttemptOutcome::Reset, &[("Split", 3.0, false)]),
+            attempt(AttemptOutcome::Reset, &[("Split", 4.0, false)]),
+        ];
+        let stats = AttemptStats::from_attempts(&attempts);
+        assert_eq!((stats.best, stats.median), (None, None));
+        assert_eq!(stats.phases.len(), 1);
+        let split = &stats.phases[0];
+        assert_eq!((split.best, split.median, split.resets), (None, None, 2));
+    }
+
+    #[tokio::test]
+    async fn saving_replaces_the_history_without_leaving_a_partial_file() {
+        let dir = std::env::temp_dir().join(format!("taimi-attempts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut history = AttemptHistory::default();
+        history.record(attempt(AttemptOutcome::Finished, &[("Split", 10.0, true)]));
+        history.save(&dir).await.unwrap();
+        history.record(attempt(AttemptOutcome::Reset, &[("Split", 2.0, false)]));
+        history.save(&dir).await.unwrap();
+
+        let loaded = AttemptHistory::load(&dir).await.unwrap();
+        let leftover = dir.join("history.json.tmp").exists();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(loaded.timers["test"].len(), 2);
+        assert!(!leftover);
+    }
+}
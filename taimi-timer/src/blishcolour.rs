@@ -13,15 +13,29 @@ pub struct BlishColour {
     red: u8,
     green: u8,
     blue: u8,
+    /// From 0 to 1, and fully opaque when a colour is given as just red, green and blue.
     #[palette(alpha)]
+    #[serde(default = "default_alpha")]
     alpha: f32,
 }
 
+fn default_alpha() -> f32 {
+    1.0
+}
+
 impl BlishColour {
+    pub fn new(red: u8, green: u8, blue: u8, alpha: f32) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
     pub fn imgcolor(self) -> [f32; 4] {
         let srgb: Srgb = self.into_color_unclamped();
-        //let alpha = 1.0 - self.alpha;
-        [srgb.red, srgb.blue, srgb.green, 1.0]
+        [srgb.red, srgb.green, srgb.blue, self.alpha]
     }
 }
 
@@ -0,0 +1,50 @@
+use {
+    std::{fmt::Debug, sync::RwLock},
+    tokio::time::{Duration, Instant},
+};
+
+/**
+ * Where a timer gets its sense of time from.
+ *
+ * In game this is just the system clock, but a simulation can drive it by hand.
+ */
+pub trait Clock: Debug + Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: RwLock<Instant>,
+}
+
+impl ManualClock {
+    pub fn new(start: Instant) -> Self {
+        Self {
+            now: RwLock::new(start),
+        }
+    }
+
+    pub fn set(&self, now: Instant) {
+        *self.now.write().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.now.write().unwrap() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.read().unwrap()
+    }
+}
@@ -6,18 +6,32 @@ use {
     tokio::time::{Duration, Instant},
 };
 
+fn default_anim_speed() -> f32 {
+    1.0
+}
+
+fn default_duration() -> f32 {
+    10.0
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BlishDirection {
-    name: String,
-    destination: BlishVec3,
-    texture: RelativePathBuf,
-    anim_speed: f32,
-    duration: f32,
-    timestamps: Vec<f32>,
+    #[serde(default)]
+    pub name: String,
+    pub destination: BlishVec3,
+    #[serde(default)]
+    pub texture: Option<RelativePathBuf>,
+    #[serde(default = "default_anim_speed")]
+    pub anim_speed: f32,
+    #[serde(default = "default_duration")]
+    pub duration: f32,
+    #[serde(default)]
+    pub timestamps: Vec<f32>,
+    #[serde(default)]
+    pub set: Option<String>,
 }
 
-#[allow(dead_code)]
 impl BlishDirection {
     fn direction(&self, timestamp: f32) -> TimerDirection {
         let destination = self.destination.to_vec3();
@@ -28,6 +42,7 @@ impl BlishDirection {
             duration: self.duration,
             destination,
             timestamp,
+            set: self.set.clone(),
         }
     }
 
@@ -40,17 +55,16 @@ impl BlishDirection {
 }
 
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct TimerDirection {
-    name: String,
-    destination: Vec3,
-    texture: RelativePathBuf,
-    anim_speed: f32,
-    duration: f32,
-    timestamp: f32,
+    pub name: String,
+    pub destination: Vec3,
+    pub texture: Option<RelativePathBuf>,
+    pub anim_speed: f32,
+    pub duration: f32,
+    pub timestamp: f32,
+    pub set: Option<String>,
 }
 
-#[allow(dead_code)]
 impl TimerDirection {
     pub fn raw_timestamp(&self) -> Duration {
         Duration::from_secs_f32(self.timestamp)
@@ -69,7 +83,13 @@ impl TimerDirection {
     pub fn start(&self, start: Instant) -> Instant {
         start + self.timestamp()
     }
-    pub fn remaining(&self, start: Instant) -> Duration {
-        self.end(start).saturating_duration_since(Instant::now())
+    pub fn is_active(&self, start: Instant, now: Instant) -> bool {
+        now >= self.start(start) && now < self.end(start)
+    }
+    /// How far the guide animation has scrolled, in texture repeats.
+    pub fn scroll(&self, start: Instant, now: Instant) -> f32 {
+        now.saturating_duration_since(self.start(start))
+            .as_secs_f32()
+            * self.anim_speed
     }
 }
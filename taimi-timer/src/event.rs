@@ -0,0 +1,43 @@
+use {
+    crate::{PhaseState, QueuedAlert, TextAlert, TimerFile},
+    std::{sync::Arc, time::Duration},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseChange {
+    Started,
+    Finished,
+    Reset,
+}
+
+/**
+ * Everything a timer machine has to tell the world, in the order it happened.
+ *
+ * The machine doesn't know who is listening: in game, these are handed on to the
+ * renderer, sounds, the 3D space, the state API and other addons, while a simulation
+ * just writes them down.
+ */
+pub enum TimerEvent {
+    /// A phase has started, along with everything it will show.
+    Feed(PhaseState),
+    /// Whatever the timer was showing is to be cleared away.
+    Reset(Arc<TimerFile>),
+    /// A skip time action moved the running phase on by `by`, which is still running.
+    Skip {
+        phase_state: PhaseState,
+        by: Duration,
+    },
+    /// A text alert is due on screen.
+    AlertStart(TextAlert),
+    /// The timer's text alerts are to be taken off screen.
+    AlertEnd(Arc<TimerFile>),
+    /// The text alerts the timer has coming up.
+    AlertQueue(Arc<TimerFile>, Vec<QueuedAlert>),
+    PhaseChanged {
+        timer: Arc<TimerFile>,
+        phase: String,
+        /// Which of the timer's phases this is, counting from zero.
+        index: usize,
+        change: PhaseChange,
+    },
+}
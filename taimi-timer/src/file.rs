@@ -1,24 +1,25 @@
 use {
-    crate::{
-        settings::RemoteSource,
-        timer::{TimerPhase, TimerTrigger},
-    },
+    crate::{TextAlertPriority, TimerPhase, TimerTrigger},
     anyhow::anyhow,
     glob::Paths,
     relative_path::RelativePathBuf,
     serde::{Deserialize, Serialize},
     std::{
+        fmt,
         path::{Path, PathBuf},
         sync::Arc,
     },
     tokio::{fs::read_to_string, sync::Semaphore, task::JoinSet},
 };
 
+/// Where a timer file came from, such as a data source the user has installed.
+pub trait TimerSource: fmt::Display + fmt::Debug + Send + Sync {}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TimerFile {
     #[serde(default, skip)]
-    pub association: Option<Arc<RemoteSource>>,
+    pub association: Option<Arc<dyn TimerSource>>,
     pub path: Option<PathBuf>,
     pub id: String,
     pub name: String,
@@ -28,9 +29,43 @@ pub struct TimerFile {
     pub icon: RelativePathBuf,
     // I probably don't need to do this, but it's convenient :o
     #[serde(rename = "map")]
-    pub map_id: u32,
+    pub map_ids: MapIds,
     pub reset: TimerTrigger,
     pub phases: Vec<TimerPhase>,
+    /// How the timer's text alerts rank against other timers' when more are due than fit.
+    #[serde(default)]
+    pub priority: TextAlertPriority,
+}
+
+/**
+ * Blish timers only ever name one map, but the same encounter is often spread across
+ * several map IDs, e.g. normal and challenge mode instances.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum MapIds {
+    One(u32),
+    Many(Vec<u32>),
+}
+
+impl MapIds {
+    pub fn as_slice(&self) -> &[u32] {
+        match self {
+            Self::One(map_id) => std::slice::from_ref(map_id),
+            Self::Many(map_ids) => map_ids,
+        }
+    }
+
+    pub fn contains(&self, map_id: u32) -> bool {
+        self.as_slice().contains(&map_id)
+    }
+}
+
+impl fmt::Display for MapIds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let map_ids: Vec<String> = self.as_slice().iter().map(u32::to_string).collect();
+        write!(f, "{}", map_ids.join(", "))
+    }
 }
 
 impl TimerFile {
@@ -39,7 +74,7 @@ impl TimerFile {
     }
 
     pub fn path_glob(path: &Path) -> PathBuf {
-        path.join(&Self::glob())
+        path.join(Self::glob())
     }
 
     pub fn get_paths(path: &Path) -> anyhow::Result<Paths> {
@@ -53,7 +88,7 @@ impl TimerFile {
 
     pub async fn load(
         path: &PathBuf,
-        source: Option<Arc<RemoteSource>>,
+        source: Option<Arc<dyn TimerSource>>,
     ) -> anyhow::Result<Arc<Self>> {
         log::debug!("Attempting to load the timer file at \"{path:?}\".");
         let mut file_data = read_to_string(path).await?;
@@ -72,12 +107,14 @@ impl TimerFile {
         log::debug!("Beginning load_many for {load_dir:?} with a simultaneous open limit of {simultaneous_limit}.");
         let mut set = JoinSet::new();
         let semaphore = Arc::new(Semaphore::new(simultaneous_limit));
-        let mut paths = Self::get_paths(load_dir)?;
-        while let Some(path) = paths.next() {
+        let paths = Self::get_paths(load_dir)?;
+        for path in paths {
             let permit = semaphore.clone().acquire_owned().await?;
             let path = path?.clone();
             set.spawn(async move {
-                let timer_file = Self::load(&path, None).await?;
+                let timer_file = Self::load(&path, None)
+                    .await
+                    .map_err(|err| anyhow!("{path:?}: {err}"))?;
                 drop(permit);
                 Ok::<Arc<TimerFile>, anyhow::Error>(timer_file)
             });
@@ -110,19 +147,21 @@ impl TimerFile {
 
     pub async fn load_many(
         load_dir: &Path,
-        source: Arc<RemoteSource>,
+        source: Arc<dyn TimerSource>,
         simultaneous_limit: usize,
     ) -> anyhow::Result<Vec<Arc<Self>>> {
         log::debug!("Beginning load_many for {load_dir:?} with a simultaneous open limit of {simultaneous_limit}.");
         let mut set = JoinSet::new();
         let semaphore = Arc::new(Semaphore::new(simultaneous_limit));
-        let mut paths = Self::get_paths(load_dir)?;
-        while let Some(path) = paths.next() {
+        let paths = Self::get_paths(load_dir)?;
+        for path in paths {
             let permit = semaphore.clone().acquire_owned().await?;
             let path = path?.clone();
             let source = source.clone();
             set.spawn(async move {
-                let timer_file = Self::load(&path, Some(source)).await?;
+                let timer_file = Self::load(&path, Some(source))
+                    .await
+                    .map_err(|err| anyhow!("{path:?}: {err}"))?;
                 drop(permit);
                 Ok::<Arc<TimerFile>, anyhow::Error>(timer_file)
             });
@@ -164,7 +203,7 @@ impl TimerFile {
     }
     pub fn source(&self) -> String {
         match &self.association {
-            Some(s) => s.source().to_string(),
+            Some(s) => s.to_string(),
             None => "".to_string(),
         }
     }
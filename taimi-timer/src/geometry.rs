@@ -43,6 +43,21 @@ impl Position {
             Self::Vec2(vec) => vec,
         }
     }
+
+    // a 2d position is everywhere at once, vertically speaking
+    fn height(self) -> Option<f32> {
+        match self {
+            Self::Vec3(vec) => Some(vec.y),
+            Self::Vec2(_vec) => None,
+        }
+    }
+
+    fn within_heights(self, min_height: Option<f32>, max_height: Option<f32>) -> bool {
+        let Some(height) = self.height() else {
+            return true;
+        };
+        min_height.is_none_or(|min| height >= min) && max_height.is_none_or(|max| height <= max)
+    }
     /*
      * this turns a 3d position into a 2d position :)
      * they wouldn't let me name it 23d
@@ -210,13 +225,58 @@ impl PartialOrd<Vec2> for Position {
 
 // one day someone is going to look at this and think i'm deranged
 // And that's Ok! they're right, i am :)
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum Polytope {
-    NSphere { center: Position, radius: f32 },
-    NCuboid { pode: Position, antipode: Position },
+    NSphere {
+        center: Position,
+        radius: f32,
+    },
+    NCuboid {
+        pode: Position,
+        antipode: Position,
+    },
+    /// Upright, with its base and top at the given heights, when they're given.
+    NCylinder {
+        center: Vec2,
+        radius: f32,
+        min_height: Option<f32>,
+        max_height: Option<f32>,
+    },
+    /// A 2d polygon on the ground, extruded upwards.
+    NPrism {
+        vertices: Vec<Vec2>,
+        min_height: Option<f32>,
+        max_height: Option<f32>,
+    },
+    /// A box turned `yaw` radians about the vertical axis through its center.
+    OrientedBox {
+        center: Vec3,
+        half_extents: Vec3,
+        yaw: f32,
+    },
 }
 
 impl Polytope {
+    // even-odd ray casting, along +x
+    fn polygon_contains(vertices: &[Vec2], point: Vec2) -> bool {
+        let mut inside = false;
+        let mut previous = match vertices.last() {
+            Some(last) => *last,
+            None => return false,
+        };
+        for &vertex in vertices {
+            if (vertex.y > point.y) != (previous.y > point.y) {
+                let crossing =
+                    vertex.x + (point.y - vertex.y) / (previous.y - vertex.y) * (previous.x - vertex.x);
+                if point.x < crossing {
+                    inside = !inside;
+                }
+            }
+            previous = vertex;
+        }
+        inside
+    }
+
     pub fn point_is_within(&self, player: Position) -> bool {
         match self {
             Polytope::NSphere { radius, center } => {
@@ -228,6 +288,36 @@ impl Polytope {
                 let maxes = pode.max(*antipode);
                 player >= mins && player <= maxes
             }
+            Polytope::NCylinder {
+                center,
+                radius,
+                min_height,
+                max_height,
+            } => {
+                center.distance(player.to_vec2()) < *radius
+                    && player.within_heights(*min_height, *max_height)
+            }
+            Polytope::NPrism {
+                vertices,
+                min_height,
+                max_height,
+            } => {
+                Self::polygon_contains(vertices, player.to_vec2())
+                    && player.within_heights(*min_height, *max_height)
+            }
+            Polytope::OrientedBox {
+                center,
+                half_extents,
+                yaw,
+            } => {
+                // turn the player into the box's frame, where it is axis-aligned again
+                let offset = player.to_vec2() - center.xz();
+                let local = Vec2::from_angle(-yaw).rotate(offset);
+                let height = player.height().map(|height| height - center.y);
+                local.x.abs() <= half_extents.x
+                    && local.y.abs() <= half_extents.z
+                    && height.is_none_or(|height| height.abs() <= half_extents.y)
+            }
         }
     }
 }
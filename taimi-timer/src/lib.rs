@@ -0,0 +1,50 @@
+//! The timer model shared by the addon and its tools: loading `.bhtimer` files and
+//! running them, without anything tied to the game or Nexus.
+
+pub mod action;
+pub mod alert;
+pub mod alert_scheduler;
+pub mod attempt;
+pub mod blishcolour;
+pub mod clock;
+pub mod direction;
+pub mod event;
+pub mod file;
+pub mod geometry;
+pub mod marker;
+pub mod overrides;
+pub mod phase;
+pub mod simulation;
+pub mod sound;
+pub mod state_machine;
+pub mod template;
+pub mod trigger;
+
+pub use {
+    action::{TimerAction, TimerActionType},
+    alert::{BlishAlert, TimerAlert, TimerAlertType},
+    alert_scheduler::{AlertScheduler, QueuedAlert, TextAlertPriority},
+    attempt::{
+        Attempt, AttemptHistory, AttemptOutcome, AttemptRecorder, AttemptStats, PhaseStats,
+        PhaseTime,
+    },
+    blishcolour::BlishColour,
+    clock::{Clock, ManualClock, SystemClock},
+    direction::{BlishDirection, TimerDirection},
+    event::{PhaseChange, TimerEvent},
+    file::{MapIds, TimerFile, TimerSource},
+    geometry::{BlishPosition, BlishVec3, Polytope, Position},
+    marker::{BlishMarker, RotationType, TimerMarker},
+    overrides::{AlertOverrides, TimerOverrides},
+    phase::TimerPhase,
+    simulation::{
+        Simulation, SimulationSample, TimelineEntry, TimelineEvent, TraceInput, TraceRecord,
+    },
+    sound::{BlishSound, TimerSound},
+    state_machine::{PhaseState, TextAlert, TimerKeybinds, TimerMachine},
+    template::{AlertPlayer, AlertVariables, RemainingFormat},
+    trigger::{
+        BreakbarState, CombatState, CombatTriggerEvent, TimerTrigger, TimerTriggerType,
+        TriggerAgent, TriggerInputs,
+    },
+};
@@ -1,5 +1,5 @@
 use {
-    crate::timer::BlishVec3,
+    crate::BlishVec3,
     glam::{Mat4, Vec3},
     serde::{Deserialize, Serialize},
     std::path::PathBuf,
@@ -7,15 +7,15 @@ use {
 };
 
 fn default_size() -> f32 {
-    return 1.0;
+    1.0
 }
 
 fn default_opacity() -> f32 {
-    return 0.8;
+    0.8
 }
 
 fn default_duration() -> f32 {
-    return 10.0;
+    10.0
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -36,6 +36,24 @@ pub struct BlishMarker {
     pub duration: f32,
     #[serde(default)]
     pub timestamps: Vec<f32>,
+    #[serde(default)]
+    pub set: Option<String>,
+}
+
+impl Default for BlishMarker {
+    fn default() -> Self {
+        Self {
+            position: Default::default(),
+            rotation: Default::default(),
+            size: default_size(),
+            fade_center: false,
+            opacity: default_opacity(),
+            texture: Default::default(),
+            duration: default_duration(),
+            timestamps: Default::default(),
+            set: Default::default(),
+        }
+    }
 }
 
 impl BlishMarker {
@@ -56,6 +74,7 @@ impl BlishMarker {
             texture: self.texture.clone(),
             timestamp,
             kind,
+            set: self.set.clone(),
         }
     }
 
@@ -64,13 +83,13 @@ impl BlishMarker {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub enum RotationType {
     Rotation(Vec3),
     Billboard,
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct TimerMarker {
     pub kind: RotationType,
@@ -80,6 +99,7 @@ pub struct TimerMarker {
     pub texture: PathBuf,
     pub timestamp: f32,
     pub duration: f32,
+    pub set: Option<String>,
 }
 
 impl TimerMarker {
@@ -0,0 +1,51 @@
+use {
+    crate::{BlishColour, TimerAlert, TimerAlertType},
+    std::collections::HashMap,
+};
+
+/// A user's changes to a timer, in the shape its phases apply them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimerOverrides {
+    /// Seconds added to every alert in the timer; negative values bring them forward.
+    pub offset: f32,
+    /// Keyed by the phase's position in the timer, then the alert's position within it.
+    pub alerts: HashMap<(usize, usize), AlertOverrides>,
+}
+
+/// A user's changes to one of a phase's alerts, covering both its alert and its warning.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertOverrides {
+    pub offset: f32,
+    pub mute_alert: bool,
+    pub mute_warning: bool,
+    pub alert: Option<String>,
+    pub warning: Option<String>,
+    pub alert_colour: Option<BlishColour>,
+    pub warning_colour: Option<BlishColour>,
+    pub fill_colour: Option<BlishColour>,
+}
+
+impl TimerOverrides {
+    pub fn alert(&self, phase: usize, index: usize) -> Option<&AlertOverrides> {
+        self.alerts.get(&(phase, index))
+    }
+}
+
+impl AlertOverrides {
+    /// The alert as the user would like it, or nothing if they have muted it.
+    pub fn apply(&self, mut alert: TimerAlert) -> Option<TimerAlert> {
+        let (muted, text, colour) = match alert.kind {
+            TimerAlertType::Alert => (self.mute_alert, &self.alert, self.alert_colour),
+            TimerAlertType::Warning => (self.mute_warning, &self.warning, self.warning_colour),
+        };
+        if muted {
+            return None;
+        }
+        if let Some(text) = text {
+            alert.text = text.clone();
+        }
+        alert.colour = colour.or(alert.colour);
+        alert.fill_colour = self.fill_colour.or(alert.fill_colour);
+        Some(alert)
+    }
+}
@@ -1,11 +1,11 @@
 use {
-    super::{direction::BlishDirection, BlishMarker, TimerMarker},
-    crate::timer::{BlishAlert, TimerAction, TimerAlert, TimerTrigger},
+    super::{BlishDirection, BlishMarker, BlishSound, TimerDirection, TimerMarker, TimerSound},
+    crate::{BlishAlert, TimerAction, TimerAlert, TimerOverrides, TimerTrigger},
     serde::{
         de::{self, Error as _, MapAccess, Visitor},
+        ser::{self, SerializeMap},
         Deserialize, Serialize,
     },
-    serde_json::Value,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,32 +19,23 @@ pub struct TimerPhase {
     pub alerts: Vec<BlishAlert>,
     #[serde(default)]
     pub actions: Vec<TimerAction>,
-    /*
-     * Not yet implemented:
-     * - directions
-     * - markers
-     * - sounds
-     */
-    #[serde(skip, default)]
-    #[allow(dead_code)]
-    directions: Vec<BlishDirection>,
+    #[serde(default)]
+    pub directions: Vec<BlishDirection>,
     #[serde(flatten, default)]
     pub markers: BlishMarkers,
-    #[serde(skip, default)]
-    #[allow(dead_code)]
-    sounds: Value,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
-struct BlishMarkersHolder {
-    pub markers: BlishMarkers,
+    #[serde(default)]
+    pub sounds: Vec<BlishSound>,
 }
 
 impl TimerPhase {
-    pub fn get_alerts(&self) -> Vec<TimerAlert> {
+    /// The alerts of the timer's `phase`th phase, with the user's overrides applied.
+    pub fn get_alerts(&self, phase: usize, overrides: &TimerOverrides) -> Vec<TimerAlert> {
         self.alerts
             .iter()
-            .flat_map(BlishAlert::get_alerts)
+            .enumerate()
+            .flat_map(|(index, alert)| {
+                alert.get_alerts(overrides.offset, overrides.alert(phase, index))
+            })
             .collect()
     }
     pub fn get_markers(&self) -> Vec<TimerMarker> {
@@ -54,11 +45,37 @@ impl TimerPhase {
             .flat_map(BlishMarker::get_markers)
             .collect()
     }
+    pub fn get_directions(&self) -> Vec<TimerDirection> {
+        self.directions
+            .iter()
+            .flat_map(BlishDirection::get_directions)
+            .collect()
+    }
+    pub fn get_sounds(&self) -> Vec<TimerSound> {
+        self.sounds
+            .iter()
+            .flat_map(BlishSound::get_sounds)
+            .collect()
+    }
 }
 
-#[derive(Serialize, Debug, Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct BlishMarkers(pub Vec<BlishMarker>);
 
+// flattened into the phase, so this has to be a map rather than the bare list
+impl Serialize for BlishMarkers {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        if !self.0.is_empty() {
+            map.serialize_entry("markers", &self.0)?;
+        }
+        map.end()
+    }
+}
+
 impl<'de> Deserialize<'de> for BlishMarkers {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -0,0 +1,330 @@
+use {
+    super::{
+        CombatState, CombatTriggerEvent, ManualClock, PhaseState, Position, TimerAlertType,
+        TimerEvent, TimerFile, TimerMachine,
+    },
+    glam::Vec3,
+    serde::{Deserialize, Serialize},
+    std::{path::PathBuf, sync::Arc},
+    tokio::{
+        sync::mpsc::{channel, Receiver},
+        time::{Duration, Instant},
+    },
+};
+
+/// One MumbleLink-ish observation of the world, `time` seconds into the simulation.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationSample {
+    pub time: f32,
+    pub position: Position,
+    pub combat: CombatState,
+    /// Indices of the timer key triggers pressed at this sample.
+    #[serde(default)]
+    pub keys: Vec<u32>,
+    /// ArcDPS combat events seen since the previous sample.
+    #[serde(default)]
+    pub combat_events: Vec<CombatTriggerEvent>,
+    pub map_id: u32,
+}
+
+impl SimulationSample {
+    /// Turns a recorded trace into samples, one for each MumbleLink read after the map is known.
+    pub fn from_trace(records: &[TraceRecord]) -> Vec<Self> {
+        let mut samples = Vec::new();
+        let mut map_id = None;
+        let mut combat = CombatState::Outside;
+        let mut in_combat = false;
+        let mut keys = Vec::new();
+        let mut combat_events = Vec::new();
+        for record in records {
+            match &record.input {
+                TraceInput::Identity { map_id: id } => map_id = Some(*id),
+                TraceInput::Combat(state) => combat = *state,
+                TraceInput::CombatEvent(event) => combat_events.push(event.clone()),
+                TraceInput::Key {
+                    index,
+                    released: false,
+                } => keys.push(*index),
+                TraceInput::Key { .. } => (),
+                TraceInput::Mumble { position, ui_state } => {
+                    let sample_in_combat = ui_state & UI_STATE_IN_COMBAT != 0;
+                    if sample_in_combat != in_combat {
+                        in_combat = sample_in_combat;
+                        combat = match in_combat {
+                            true => CombatState::Entered,
+                            false => CombatState::Exited,
+                        };
+                    }
+                    let Some(map_id) = map_id else {
+                        continue;
+                    };
+                    samples.push(SimulationSample {
+                        time: record.time,
+                        position: Position::Vec3(*position),
+                        combat,
+                        keys: std::mem::take(&mut keys),
+                        combat_events: std::mem::take(&mut combat_events),
+                        map_id,
+                    });
+                }
+            }
+        }
+        samples
+    }
+}
+
+/// MumbleLink's `uiState` bit for the player being in combat.
+const UI_STATE_IN_COMBAT: u32 = 1 << 6;
+
+/**
+ * One line of a trace recorded by the addon, keeping only what a simulation can use.
+ * Trace files are gzipped JSON lines of these.
+ */
+#[derive(Deserialize, Debug, Clone)]
+pub struct TraceRecord {
+    /// Seconds since the recording began.
+    pub time: f32,
+    #[serde(flatten)]
+    pub input: TraceInput,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceInput {
+    #[serde(rename_all = "camelCase")]
+    Mumble {
+        position: Vec3,
+        ui_state: u32,
+    },
+    Identity {
+        #[serde(alias = "mapId")]
+        map_id: u32,
+    },
+    Combat(CombatState),
+    CombatEvent(CombatTriggerEvent),
+    Key {
+        index: u32,
+        released: bool,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum TimelineEvent {
+    PhaseStarted {
+        phase: String,
+    },
+    PhaseEnded,
+    /// A skip time action moved the running phase on by `seconds`.
+    TimeSkipped {
+        phase: String,
+        seconds: f32,
+    },
+    AlertStarted {
+        kind: TimerAlertType,
+        text: String,
+    },
+    AlertEnded {
+        kind: TimerAlertType,
+        text: String,
+    },
+    MarkerSpawned {
+        texture: PathBuf,
+        position: Vec3,
+    },
+    TextAlert {
+        message: String,
+    },
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    /// Seconds since the start of the simulation.
+    pub time: f32,
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+}
+
+/**
+ * Runs a timer through a scripted sequence of samples without the game, reporting what
+ * a player would have seen and when.
+ */
+pub struct Simulation {
+    origin: Instant,
+    clock: Arc<ManualClock>,
+    machine: TimerMachine,
+    receiver: Receiver<TimerEvent>,
+    map_id: Option<u32>,
+    combat_state: CombatState,
+    running: Vec<PhaseState>,
+    last: Instant,
+    timeline: Vec<TimelineEntry>,
+}
+
+impl Simulation {
+    pub fn new(timer: Arc<TimerFile>) -> Self {
+        let origin = Instant::now();
+        let clock = Arc::new(ManualClock::new(origin));
+        let (sender, receiver) = channel(256);
+        let machine = TimerMachine::with_clock(timer, sender, clock.clone());
+        Self {
+            origin,
+            clock,
+            machine,
+            receiver,
+            map_id: None,
+            combat_state: CombatState::Outside,
+            running: Default::default(),
+            last: origin,
+            timeline: Default::default(),
+        }
+    }
+
+    /// Feeds every sample in order and hands back the resulting timeline.
+    pub async fn run(timer: Arc<TimerFile>, samples: &[SimulationSample]) -> Vec<TimelineEntry> {
+        let mut simulation = Self::new(timer);
+        for sample in samples {
+            simulation.feed(sample).await;
+        }
+        simulation.finish()
+    }
+
+    pub async fn feed(&mut self, sample: &SimulationSample) {
+        let now = self.origin + Duration::from_secs_f32(sample.time.max(0.0));
+        let now = now.max(self.last);
+        self.elapse(now);
+        self.clock.set(now);
+
+        if self.map_id != Some(sample.map_id) {
+            self.machine.update_on_map(sample.map_id);
+            self.map_id = Some(sample.map_id);
+        }
+        if self.combat_state != sample.combat {
+            self.machine.set_combat_state(sample.combat);
+            self.combat_state = sample.combat;
+        }
+        for &key in &sample.keys {
+            self.machine.key_event(key, false);
+        }
+        for event in &sample.combat_events {
+            self.machine.combat_event(event.clone());
+        }
+        self.machine.tick(sample.position).await;
+        for &key in &sample.keys {
+            self.machine.key_event(key, true);
+        }
+        self.drain(now);
+    }
+
+    pub fn finish(mut self) -> Vec<TimelineEntry> {
+        self.timeline
+            .sort_by(|a, b| a.time.total_cmp(&b.time));
+        std::mem::take(&mut self.timeline)
+    }
+
+    fn seconds(&self, at: Instant) -> f32 {
+        at.saturating_duration_since(self.origin).as_secs_f32()
+    }
+
+    fn push(&mut self, at: Instant, event: TimelineEvent) {
+        let time = self.seconds(at);
+        self.timeline.push(TimelineEntry { time, event });
+    }
+
+    fn drain(&mut self, now: Instant) {
+        while let Ok(event) = self.receiver.try_recv() {
+            match event {
+                TimerEvent::Feed(phase_state) => {
+                    self.push(
+                        now,
+                        TimelineEvent::PhaseStarted {
+                            phase: phase_state.phase.name.clone(),
+                        },
+                    );
+                    self.due(&phase_state, now, true, now);
+                    self.running.push(phase_state);
+                }
+                TimerEvent::Reset(_) => {
+                    if !self.running.is_empty() {
+                        self.push(now, TimelineEvent::PhaseEnded);
+                    }
+                    self.running.clear();
+                }
+                TimerEvent::Skip { phase_state, by } => {
+                    self.push(
+                        now,
+                        TimelineEvent::TimeSkipped {
+                            phase: phase_state.phase.name.clone(),
+                            seconds: by.as_secs_f32(),
+                        },
+                    );
+                    self.running.clear();
+                    self.due(&phase_state, now, true, now);
+                    self.running.push(phase_state);
+                }
+                TimerEvent::AlertStart(alert) => {
+                    self.push(
+                        now,
+                        TimelineEvent::TextAlert {
+                            message: alert.message,
+                        },
+                    );
+                }
+                _ => (),
+            }
+        }
+        self.last = now;
+    }
+
+    /// Records whatever the running phases did between the previous sample and `now`.
+    fn elapse(&mut self, now: Instant) {
+        let running = std::mem::take(&mut self.running);
+        for phase_state in &running {
+            self.due(phase_state, self.last, false, now);
+        }
+        self.running = running;
+    }
+
+    /**
+     * Alert and marker events that fall between `from` and `to`.
+     * Anything before a phase was fed was skipped over, rather than seen.
+     */
+    fn due(&mut self, phase_state: &PhaseState, from: Instant, inclusive: bool, to: Instant) {
+        let within = |at: Instant| (at > from || (inclusive && at == from)) && at <= to;
+        for alert in &phase_state.alerts {
+            let start = alert.start(phase_state.start);
+            if within(start) {
+                self.push(
+                    start,
+                    TimelineEvent::AlertStarted {
+                        kind: alert.kind,
+                        text: alert.text.clone(),
+                    },
+                );
+            }
+            let end = alert.end(phase_state.start);
+            if within(end) {
+                self.push(
+                    end,
+                    TimelineEvent::AlertEnded {
+                        kind: alert.kind,
+                        text: alert.text.clone(),
+                    },
+                );
+            }
+        }
+        for marker in &phase_state.markers {
+            let start = marker.start(phase_state.start);
+            if within(start) {
+                self.push(
+                    start,
+                    TimelineEvent::MarkerSpawned {
+                        texture: marker.texture.clone(),
+                        position: marker.position,
+                    },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use {
+    relative_path::RelativePathBuf,
+    serde::{Deserialize, Serialize},
+    tokio::time::{Duration, Instant},
+};
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlishSound {
+    #[serde(default)]
+    pub name: String,
+    #[serde(alias = "path", alias = "sound")]
+    pub file: RelativePathBuf,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    #[serde(default)]
+    pub timestamps: Vec<f32>,
+    #[serde(default)]
+    pub set: Option<String>,
+}
+
+impl BlishSound {
+    fn sound(&self, timestamp: f32) -> TimerSound {
+        TimerSound {
+            name: self.name.clone(),
+            file: self.file.clone(),
+            volume: self.volume.clamp(0.0, 1.0),
+            timestamp,
+            set: self.set.clone(),
+        }
+    }
+
+    pub fn get_sounds(&self) -> Vec<TimerSound> {
+        self.timestamps.iter().map(|&ts| self.sound(ts)).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimerSound {
+    pub name: String,
+    pub file: RelativePathBuf,
+    pub volume: f32,
+    pub timestamp: f32,
+    pub set: Option<String>,
+}
+
+impl TimerSound {
+    /**
+     * Unlike alerts, a sound has no duration to count down through:
+     * it simply plays at its timestamp.
+     */
+    pub fn timestamp(&self) -> Duration {
+        Duration::from_secs_f32(self.timestamp)
+    }
+    pub fn start(&self, start: Instant) -> Instant {
+        start + self.timestamp()
+    }
+}
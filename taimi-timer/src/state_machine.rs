@@ -0,0 +1,725 @@
+use {
+    super::{
+        AlertScheduler, Clock, QueuedAlert, SystemClock, TextAlertPriority, TimerDirection,
+        TimerMarker, TimerSound,
+    },
+    crate::{
+        Attempt, AttemptOutcome, AttemptRecorder, CombatState, CombatTriggerEvent, PhaseChange,
+        Position, TimerAction, TimerActionType, TimerAlert, TimerEvent, TimerFile,
+        TimerOverrides, TimerPhase, TriggerInputs,
+    },
+    bitflags::bitflags,
+    std::{fmt::Display, ops::Deref, sync::Arc},
+    tokio::{
+        sync::mpsc::Sender,
+        time::{Duration, Instant},
+    },
+};
+
+bitflags! {
+    #[derive(Debug, Clone, Default)]
+    pub struct TimerKeybinds: u8 {
+        const A = 1;
+        const B = 1 << 1;
+        const C = 1 << 2;
+        const D = 1 << 3;
+        const E = 1 << 4;
+    }
+}
+
+/*
+* A timer can be:
+* - existent without knowledge of current map
+* - off the map
+* - on the map, first phase untriggered
+* - phase triggered, cycling through alerts
+* - nth phase done, next phase
+* - finished, denoted by a different area, departure, out of combat, ...
+* - failed, with reset condition
+*/
+#[derive(Debug, Clone)]
+enum TimerMachineState {
+    /*
+     * Ensolyss: I am awake.
+     * Ensolyss: I am aware.
+     * Ensolyss: Suffer, mortal things.
+     */
+    AwakeUnaware,
+    OffMap,
+    OnMap,
+    OnPhase(TimerFilePhase),
+    FinishedPhase(TimerFilePhase),
+    Finished,
+}
+
+impl Display for TimerMachineState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use TimerMachineState::*;
+        match self {
+            AwakeUnaware => write!(f, "AwakeUnaware"),
+            OffMap => write!(f, "OffMap"),
+            OnMap => write!(f, "OnMap"),
+            OnPhase(tfp) => write!(f, "OnPhase {}", tfp.name),
+            FinishedPhase(tfp) => write!(f, "FinishedPhase {}", tfp.name),
+            Finished => write!(f, "Finished"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimerFilePhase {
+    timer: Arc<TimerFile>,
+    phase: usize,
+}
+
+impl TimerFilePhase {
+    fn new(timer: Arc<TimerFile>) -> Option<Self> {
+        match timer.phases.is_empty() {
+            true => None,
+            false => Some(Self { timer, phase: 0 }),
+        }
+    }
+
+    fn named(timer: Arc<TimerFile>, name: &str) -> Option<Self> {
+        let phase = timer.phases.iter().position(|p| p.name == name)?;
+        Some(Self { timer, phase })
+    }
+
+    fn next(self) -> Option<Self> {
+        let phase_len = self.timer.phases.len();
+        let phase = (self.phase + 1..phase_len).next()?;
+        Some(Self {
+            timer: self.timer,
+            phase,
+        })
+    }
+
+    pub fn phase(&self) -> &TimerPhase {
+        &self.timer.phases[self.phase]
+    }
+}
+
+impl Deref for TimerFilePhase {
+    type Target = TimerPhase;
+
+    fn deref(&self) -> &Self::Target {
+        self.phase()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TimerMachine {
+    state: TimerMachineState,
+    pub timer: Arc<TimerFile>,
+    sender: Sender<TimerEvent>,
+    alert_scheduler: AlertScheduler,
+    inputs: TriggerInputs,
+    phase_state: Option<PhaseState>,
+    fired_actions: Vec<usize>,
+    /// Actions that restarted their own phase, which can't fire again until their trigger lets go.
+    held_actions: Vec<usize>,
+    state_since: Instant,
+    clock: Arc<dyn Clock>,
+    attempts: AttemptRecorder,
+    overrides: TimerOverrides,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseState {
+    pub timer: Arc<TimerFile>,
+    pub start: Instant,
+    pub phase: TimerFilePhase,
+    pub alerts: Vec<TimerAlert>,
+    pub markers: Vec<TimerMarker>,
+    pub directions: Vec<TimerDirection>,
+    pub sounds: Vec<TimerSound>,
+}
+
+#[derive(Clone)]
+pub struct TextAlert {
+    pub timer: Arc<TimerFile>,
+    pub message: String,
+    pub priority: TextAlertPriority,
+    pub duration: Duration,
+}
+
+impl PhaseState {
+    /**
+     * Moves the phase clock forward by `by`, as if that much more time had passed.
+     * When `sets` are given, only the elements in one of those sets move; everything
+     * else is held in place by pushing its timestamp back by the same amount.
+     */
+    pub fn skip(&mut self, by: Duration, sets: Option<&[String]>) {
+        self.start = self.start.checked_sub(by).unwrap_or(self.start);
+        let Some(sets) = sets else {
+            return;
+        };
+        let held = |set: &Option<String>| !set.as_ref().is_some_and(|set| sets.contains(set));
+        let by = by.as_secs_f32();
+        for alert in self.alerts.iter_mut().filter(|a| held(&a.set)) {
+            alert.timestamp += by;
+        }
+        for marker in self.markers.iter_mut().filter(|m| held(&m.set)) {
+            marker.timestamp += by;
+        }
+        for direction in self.directions.iter_mut().filter(|d| held(&d.set)) {
+            direction.timestamp += by;
+        }
+        for sound in self.sounds.iter_mut().filter(|s| held(&s.set)) {
+            sound.timestamp += by;
+        }
+    }
+}
+
+impl TimerMachine {
+    pub fn new(timer: Arc<TimerFile>, sender: Sender<TimerEvent>) -> Self {
+        Self::with_clock(timer, sender, Arc::new(SystemClock))
+    }
+
+    /// A machine that takes its time from `clock`.
+    pub fn with_clock(
+        timer: Arc<TimerFile>,
+        sender: Sender<TimerEvent>,
+        clock: Arc<dyn Clock>,
+    ) -> Self {
+        TimerMachine {
+            state: TimerMachineState::AwakeUnaware,
+            timer,
+            sender,
+            alert_scheduler: Default::default(),
+            inputs: Default::default(),
+            phase_state: Default::default(),
+            fired_actions: Default::default(),
+            held_actions: Default::default(),
+            state_since: clock.now(),
+            clock,
+            attempts: Default::default(),
+            overrides: Default::default(),
+        }
+    }
+
+    /// The user's overrides, applied to each phase's alerts as it starts.
+    pub fn set_overrides(&mut self, overrides: TimerOverrides) {
+        self.overrides = overrides;
+    }
+
+    async fn send(&self, event: TimerEvent) {
+        let _ = self.sender.send(event).await;
+    }
+
+    async fn raise_phase_changed(&self, phase: &TimerFilePhase, change: PhaseChange) {
+        self.send(TimerEvent::PhaseChanged {
+            timer: self.timer.clone(),
+            phase: phase.name.clone(),
+            index: phase.phase,
+            change,
+        })
+        .await;
+    }
+
+    async fn text_alert(
+        &mut self,
+        message: String,
+        wait_duration: Duration,
+        display_duration: Duration,
+        priority: TextAlertPriority,
+    ) {
+        log::info!(
+            "Queueing {} in {:?}: a {:?} priority message with {:?} duration",
+            message,
+            wait_duration,
+            priority,
+            display_duration
+        );
+        self.alert_scheduler.schedule(QueuedAlert {
+            message,
+            show_at: self.clock.now() + wait_duration,
+            duration: display_duration,
+            priority,
+        });
+        self.send_alert_queue().await;
+    }
+
+    async fn send_alert_queue(&self) {
+        let queue = self.alert_scheduler.pending().to_vec();
+        self.send(TimerEvent::AlertQueue(self.timer.clone(), queue))
+            .await;
+    }
+
+    async fn poll_alerts(&mut self) {
+        let due = self.alert_scheduler.poll(self.clock.now());
+        if due.is_empty() {
+            return;
+        }
+        for alert in due {
+            log::info!(
+                "Displaying {}: a message with {:?} duration",
+                alert.message,
+                alert.duration
+            );
+            let alert = TextAlert {
+                timer: self.timer.clone(),
+                message: alert.message,
+                priority: alert.priority,
+                duration: alert.duration,
+            };
+            self.send(TimerEvent::AlertStart(alert)).await;
+        }
+        self.send_alert_queue().await;
+    }
+
+    async fn reset_check(&mut self, pos: Position) {
+        use TimerMachineState::*;
+        let running = matches!(self.state, OnPhase(_) | FinishedPhase(_) | Finished);
+        if running && self.timer.reset.check(pos, &mut self.inputs) {
+            self.do_reset().await;
+        }
+    }
+
+    pub async fn do_reset(&mut self) {
+        let reason = format!("Reset triggered for \"{}\"", self.timer.name);
+        log::info!("Reset triggered!");
+        let now = self.clock.now();
+        if let TimerMachineState::OnPhase(phase) | TimerMachineState::FinishedPhase(phase) =
+            &self.state
+        {
+            self.raise_phase_changed(phase, PhaseChange::Reset).await;
+        }
+        self.attempts
+            .end(&self.timer.id, AttemptOutcome::Reset, now);
+        self.inputs.combat_state = CombatState::Outside;
+        self.state_change(TimerMachineState::OnMap).await;
+        self.abort_tasks(reason.clone()).await;
+        let zero_s = Duration::from_secs(0);
+        let one_s = Duration::from_secs(1);
+        self.text_alert(reason, zero_s, one_s, self.timer.priority)
+            .await;
+    }
+
+    pub async fn cleanup(&mut self) {
+        let reason = format!(
+            "\"{}\" is being told to cleanup, about to be deleted!",
+            self.timer.name
+        );
+        self.attempts.abandon();
+        self.abort_tasks(reason).await;
+    }
+
+    /// The attempts that have finished or been reset since this was last called.
+    pub fn take_attempts(&mut self) -> Vec<Attempt> {
+        self.attempts.take()
+    }
+
+    async fn abort_tasks(&mut self, reason: String) {
+        log::info!(
+            "Cancelling {} queued alerts for reason: \"{}\".",
+            self.alert_scheduler.pending().len(),
+            reason
+        );
+        let was_shown = self.alert_scheduler.cancel();
+        if was_shown {
+            self.send(TimerEvent::AlertEnd(self.timer.clone())).await;
+        }
+        self.send_alert_queue().await;
+        self.send(TimerEvent::Reset(self.timer.clone())).await;
+    }
+
+    async fn start_tasks(&mut self, phase: &TimerFilePhase) {
+        let alerts = phase.get_alerts(phase.phase, &self.overrides);
+        let markers = phase.get_markers();
+        let directions = phase.get_directions();
+        let sounds = phase.get_sounds();
+        let phase_state = PhaseState {
+            timer: self.timer.clone(),
+            start: self.clock.now(),
+            phase: phase.clone(),
+            alerts,
+            markers,
+            directions,
+            sounds,
+        };
+        self.phase_state = Some(phase_state.clone());
+        self.fired_actions.clear();
+        self.send(TimerEvent::Feed(phase_state)).await;
+    }
+
+    async fn run_action(&mut self, phase: &TimerFilePhase, action: &TimerAction) {
+        log::info!(
+            "Action \"{}\" ({:?}) triggered for \"{}\" in phase \"{}\"",
+            action.name,
+            action.kind,
+            self.timer.name(),
+            phase.name
+        );
+        use TimerActionType::*;
+        match action.kind {
+            SkipTime => {
+                let Some(time) = action.time.filter(|t| *t > 0.0) else {
+                    log::warn!("Skip time action \"{}\" has no time to skip", action.name);
+                    return;
+                };
+                let Some(mut phase_state) = self.phase_state.take() else {
+                    return;
+                };
+                let by = Duration::from_secs_f32(time);
+                phase_state.skip(by, action.sets.as_deref());
+                self.state_since = self.state_since.checked_sub(by).unwrap_or(self.state_since);
+                self.phase_state = Some(phase_state.clone());
+                self.send(TimerEvent::Skip { phase_state, by }).await;
+            }
+            JumpToPhase => {
+                let target = action
+                    .phase
+                    .as_deref()
+                    .and_then(|name| TimerFilePhase::named(self.timer.clone(), name));
+                match target {
+                    Some(target) => {
+                        self.state_change(TimerMachineState::OnPhase(target)).await
+                    }
+                    None => log::warn!(
+                        "Jump action \"{}\" names a phase {:?} that does not exist",
+                        action.name,
+                        action.phase
+                    ),
+                }
+            }
+            RestartPhase => {
+                self.state_change(TimerMachineState::OnPhase(phase.clone()))
+                    .await
+            }
+        }
+    }
+
+    /// Returns whether an action fired, as it may have moved the machine on to another phase.
+    async fn actions_check(&mut self, phase: &TimerFilePhase, pos: Position) -> bool {
+        for (idx, action) in phase.actions.iter().enumerate() {
+            if self.fired_actions.contains(&idx) {
+                continue;
+            }
+            let triggered = action.trigger.check(pos, &mut self.inputs);
+            if self.held_actions.contains(&idx) {
+                if !triggered {
+                    self.held_actions.retain(|&held| held != idx);
+                }
+                continue;
+            }
+            if triggered {
+                self.fired_actions.push(idx);
+                self.run_action(phase, action).await;
+                return true;
+            }
+        }
+        false
+    }
+
+    /**
+        state_change is about code that should run once, upon a stage or phase change.
+    */
+    async fn state_change(&mut self, state: TimerMachineState) {
+        use TimerMachineState::*;
+        let now = self.clock.now();
+        if let FinishedPhase(ref phase) = state {
+            self.attempts.phase_finished(now);
+            self.raise_phase_changed(phase, PhaseChange::Finished)
+                .await;
+        }
+        let final_state = match state {
+            FinishedPhase(ref phase) if phase.clone().next().is_none() => Finished,
+            _ => state,
+        };
+        match &final_state {
+            OnPhase(phase) => {
+                self.attempts.phase_started(&phase.name, now);
+                self.raise_phase_changed(phase, PhaseChange::Started)
+                    .await;
+            }
+            Finished => self
+                .attempts
+                .end(&self.timer.id, AttemptOutcome::Finished, now),
+            _ => (),
+        }
+        let reason = format!("Switching from state {} to {}", self.state, final_state);
+        self.abort_tasks(reason).await;
+        self.phase_state = None;
+        self.state_since = now;
+        self.held_actions = match (&self.state, &final_state) {
+            (OnPhase(from), OnPhase(to)) if from.phase == to.phase => {
+                std::mem::take(&mut self.fired_actions)
+            }
+            _ => Vec::new(),
+        };
+        if let OnPhase(phase) = &final_state {
+            self.start_tasks(phase).await;
+        }
+        self.state = final_state;
+    }
+
+    /**
+     * tick, in comparison to state_change, runs perpetually and is used for
+     * checking to see if conditions for a next phase are met
+     */
+    pub async fn tick(&mut self, pos: Position) {
+        self.tick_state(pos).await;
+        self.poll_alerts().await;
+        // combat events only get the one chance to trigger something
+        self.inputs.combat_events.clear();
+    }
+
+    async fn tick_state(&mut self, pos: Position) {
+        self.inputs.elapsed = self
+            .clock
+            .now()
+            .saturating_duration_since(self.state_since);
+        // It is always important to check if we have met the conditions for resetting the timer
+        self.reset_check(pos).await;
+
+        use TimerMachineState::*;
+        match &self.state {
+            // We exist, but is there anything to do about that?
+            // Nothing, without the current map. Lost adrift in the void.
+            AwakeUnaware => (),
+            // We're off map, this means the timer conditions cannot be met.
+            OffMap => (),
+            // OnMap means time to start looking for our conditions, with location and
+            // (unimplemented) key first.
+            OnMap => {
+                // All timers have a start trigger and a zeroth (first) phase
+                let trigger = &self.timer.phases.first().unwrap().start;
+                if trigger.check(pos, &mut self.inputs) {
+                    if let Some(phase) = TimerFilePhase::new(self.timer.clone()) {
+                        self.state_change(OnPhase(phase)).await;
+                    }
+                }
+            }
+            // within a phase (nth)
+            OnPhase(phase) => {
+                let phase = phase.clone();
+                if self.actions_check(&phase, pos).await {
+                    return;
+                }
+                // handle the finish check
+                if let Some(trigger) = &phase.finish {
+                    if trigger.check(pos, &mut self.inputs) {
+                        self.state_change(FinishedPhase(phase.clone())).await;
+                    }
+                }
+            }
+            FinishedPhase(phase) => {
+                // check the next phase's start trigger
+                if let Some(next_phase) = &phase.clone().next() {
+                    let trigger = &next_phase.start;
+                    if trigger.check(pos, &mut self.inputs) {
+                        self.state_change(OnPhase(next_phase.clone())).await;
+                    }
+                }
+            }
+            Finished => (),
+        }
+    }
+
+    pub fn key_event(&mut self, idx: u32, is_release: bool) {
+        let flag = 1u8 << idx;
+        match is_release {
+            false => self
+                .inputs
+                .key_pressed
+                .insert(TimerKeybinds::from_bits_retain(flag)),
+            true => self
+                .inputs
+                .key_pressed
+                .remove(TimerKeybinds::from_bits_retain(flag)),
+        }
+    }
+
+    pub fn set_combat_state(&mut self, combat_state: CombatState) {
+        self.inputs.combat_state = combat_state;
+    }
+
+    pub fn combat_event(&mut self, event: CombatTriggerEvent) {
+        use TimerMachineState::*;
+        // nothing is listening for them while off the map
+        if matches!(self.state, AwakeUnaware | OffMap) {
+            return;
+        }
+        self.inputs.combat_events.push(event);
+    }
+
+    pub fn update_on_map(&mut self, map_id: u32) {
+        if self.timer.map_ids.contains(map_id) {
+            log::info!(
+                "On map with ID \"{}\" for \"{}\"",
+                map_id,
+                self.timer.name()
+            );
+            self.state = TimerMachineState::OnMap;
+            self.state_since = self.clock.now();
+        } else {
+            log::info!(
+                "Off map with ID \"{}\" for \"{}\"",
+                map_id,
+                self.timer.name()
+            );
+            self.state = TimerMachineState::OffMap;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::ManualClock,
+        glam::Vec3,
+        tokio::sync::mpsc::{channel, Receiver},
+    };
+
+    const MAP_ID: u32 = 1;
+    const TICK: Duration = Duration::from_millis(20);
+
+    /// A single phase that restarts itself while the player stands in the same spot that
+    /// started it.
+    fn restarting_timer() -> Arc<TimerFile> {
+        let zone = serde_json::json!({
+            "position": [0.0, 0.0, 0.0],
+            "radius": 5.0,
+            "requireEntry": true,
+        });
+        let timer = serde_json::json!({
+            "id": "restart",
+            "name": "Restart",
+            "category": "",
+            "description": "",
+            "author": "",
+            "icon": "",
+            "map": MAP_ID,
+            "reset": {
+                "position": [500.0, 500.0, 500.0],
+                "radius": 5.0,
+                "requireEntry": true,
+            },
+            "phases": [{
+                "name": "Only",
+                "start": zone,
+                "actions": [{
+                    "name": "Again",
+                    "type": "restartPhase",
+                    "trigger": zone,
+                }],
+            }],
+        });
+        Arc::new(serde_json::from_value(timer).unwrap())
+    }
+
+    /// Two phases, the first finished by a key that a reset group also looks at.
+    fn key_finished_timer() -> Arc<TimerFile> {
+        let zone = serde_json::json!({
+            "position": [0.0, 0.0, 0.0],
+            "radius": 5.0,
+            "requireEntry": true,
+        });
+        let timer = serde_json::json!({
+            "id": "key",
+            "name": "Key",
+            "category": "",
+            "description": "",
+            "author": "",
+            "icon": "",
+            "map": MAP_ID,
+            "reset": {
+                "type": "all",
+                "triggers": [
+                    { "type": "key", "keyBind": "0", "position": [0.0, 0.0, 0.0], "radius": 5.0 },
+                    { "position": [500.0, 500.0, 500.0], "radius": 5.0, "requireEntry": true },
+                ],
+            },
+            "phases": [{
+                "name": "First",
+                "start": zone,
+                "finish": { "type": "key", "keyBind": "0", "position": [0.0, 0.0, 0.0], "radius": 5.0 },
+            }, {
+                "name": "Second",
+                "start": zone,
+            }],
+        });
+        Arc::new(serde_json::from_value(timer).unwrap())
+    }
+
+    struct Harness {
+        machine: TimerMachine,
+        receiver: Receiver<TimerEvent>,
+        clock: Arc<ManualClock>,
+    }
+
+    impl Harness {
+        fn new(timer: Arc<TimerFile>) -> Self {
+            let clock = Arc::new(ManualClock::new(Instant::now()));
+            let (sender, receiver) = channel(64);
+            let mut machine = TimerMachine::with_clock(timer, sender, clock.clone());
+            machine.update_on_map(MAP_ID);
+            Self {
+                machine,
+                receiver,
+                clock,
+            }
+        }
+
+        /// Ticks the machine at `at` for a while, returning how many times a phase started.
+        async fn starts_while_at(&mut self, at: Vec3, ticks: usize) -> usize {
+            let mut starts = 0;
+            for _ in 0..ticks {
+                self.clock.advance(TICK);
+                self.machine.tick(Position::Vec3(at)).await;
+                while let Ok(event) = self.receiver.try_recv() {
+                    if matches!(event, TimerEvent::Feed(_)) {
+                        starts += 1;
+                    }
+                }
+            }
+            starts
+        }
+
+        /// Ticks the machine at `at` once, returning the phases that started.
+        async fn started_at(&mut self, at: Vec3) -> Vec<String> {
+            self.clock.advance(TICK);
+            self.machine.tick(Position::Vec3(at)).await;
+            let mut started = Vec::new();
+            while let Ok(event) = self.receiver.try_recv() {
+                if let TimerEvent::PhaseChanged {
+                    phase,
+                    change: PhaseChange::Started,
+                    ..
+                } = event
+                {
+                    started.push(phase);
+                }
+            }
+            started
+        }
+    }
+
+    #[tokio::test]
+    async fn restart_phase_waits_for_its_trigger_to_let_go() {
+        let mut harness = Harness::new(restarting_timer());
+        let inside = Vec3::ZERO;
+        let outside = Vec3::new(50.0, 0.0, 50.0);
+
+        // started, then restarted once by the action, since the zone starts both
+        assert_eq!(harness.starts_while_at(inside, 2).await, 2);
+        assert_eq!(harness.starts_while_at(inside, 50).await, 0);
+        assert_eq!(harness.starts_while_at(outside, 5).await, 0);
+        assert_eq!(harness.starts_while_at(inside, 50).await, 1);
+    }
+
+    #[tokio::test]
+    async fn key_under_a_group_that_does_not_fire_is_still_pressed() {
+        let mut harness = Harness::new(key_finished_timer());
+        let inside = Vec3::ZERO;
+
+        assert_eq!(harness.started_at(inside).await, ["First"]);
+        assert!(harness.started_at(inside).await.is_empty());
+        // the reset group sees the key too, but not the rest of what it's waiting for
+        harness.machine.key_event(0, false);
+        assert!(harness.started_at(inside).await.is_empty());
+        assert_eq!(harness.started_at(inside).await, ["Second"]);
+    }
+}
@@ -0,0 +1,161 @@
+use {
+    serde::{Deserialize, Serialize},
+    std::borrow::Cow,
+    strum_macros::{Display, EnumIter},
+    tokio::time::Duration,
+};
+
+/// How the time left on an alert is written, both for `{remaining}` and after its text.
+#[derive(Display, Default, Clone, Copy, Debug, Deserialize, Serialize, EnumIter, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum RemainingFormat {
+    /// `12.3s`
+    #[default]
+    Seconds,
+    /// `12s`
+    WholeSeconds,
+    /// `0:12`
+    MinutesSeconds,
+    Hidden,
+}
+
+impl RemainingFormat {
+    pub fn format(&self, remaining: Duration) -> String {
+        let secs = remaining.as_secs_f32();
+        match self {
+            Self::Seconds => format!("{secs:.1}s"),
+            Self::WholeSeconds => format!("{}s", secs.ceil() as u32),
+            Self::MinutesSeconds => {
+                let secs = secs.ceil() as u32;
+                format!("{}:{:02}", secs / 60, secs % 60)
+            }
+            Self::Hidden => String::new(),
+        }
+    }
+}
+
+/// Who is playing, as far as alert text is concerned.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertPlayer {
+    pub character: Option<String>,
+    pub account: Option<String>,
+    pub squad_role: Option<String>,
+}
+
+/// Everything an alert's `{placeholder}`s can be filled in from.
+pub struct AlertVariables<'a> {
+    pub remaining: Duration,
+    pub remaining_format: RemainingFormat,
+    pub phase: &'a str,
+    pub timer: &'a str,
+    pub player: &'a AlertPlayer,
+}
+
+impl AlertVariables<'_> {
+    fn get(&self, name: &str) -> Option<Cow<'_, str>> {
+        let player = |value: &Option<String>| Cow::Owned(value.clone().unwrap_or_default());
+        Some(match name {
+            "remaining" => Cow::Owned(self.remaining_format.format(self.remaining)),
+            "phase" => Cow::Borrowed(self.phase),
+            "timer" => Cow::Borrowed(self.timer),
+            "character" => player(&self.player.character),
+            "account" => player(&self.player.account),
+            "squad_role" => player(&self.player.squad_role),
+            _ => return None,
+        })
+    }
+
+    /// Whether `template` places the remaining time itself.
+    pub fn mentions_remaining(template: &str) -> bool {
+        template.contains("{remaining}")
+    }
+
+    /**
+     * Fills in the known placeholders in `template`, with `{{` and `}}` written for literal
+     * braces. Unknown placeholders and stray braces are left as they were written.
+     */
+    pub fn render(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(brace) = rest.find(['{', '}']) {
+            rendered.push_str(&rest[..brace]);
+            let after = &rest[brace + 1..];
+            if rest[brace..].starts_with('}') {
+                rendered.push('}');
+                rest = after.strip_prefix('}').unwrap_or(after);
+                continue;
+            }
+            if let Some(escaped) = after.strip_prefix('{') {
+                rendered.push('{');
+                rest = escaped;
+                continue;
+            }
+            let value = after
+                .find('}')
+                .and_then(|close| Some((close, self.get(&after[..close])?)));
+            match value {
+                Some((close, value)) => {
+                    rendered.push_str(&value);
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = after;
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(template: &str) -> String {
+        let player = AlertPlayer {
+            character: Some("Taimi".to_string()),
+            account: None,
+            squad_role: Some("Commander".to_string()),
+        };
+        let variables = AlertVariables {
+            remaining: Duration::from_millis(12_340),
+            remaining_format: RemainingFormat::Seconds,
+            phase: "Breakbar",
+            timer: "Vale Guardian",
+            player: &player,
+        };
+        variables.render(template)
+    }
+
+    #[test]
+    fn known_placeholders_are_filled_in() {
+        assert_eq!(
+            render("{timer}: {phase} in {remaining} for {character}"),
+            "Vale Guardian: Breakbar in 12.3s for Taimi"
+        );
+        assert_eq!(render("{squad_role}/{account}/"), "Commander//");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_left_alone() {
+        assert_eq!(render("{boss} {phase}"), "{boss} Breakbar");
+        assert_eq!(render("{}"), "{}");
+    }
+
+    #[test]
+    fn unterminated_placeholders_are_left_alone() {
+        assert_eq!(render("{phase"), "{phase");
+        assert_eq!(render("{ {phase}"), "{ Breakbar");
+        assert_eq!(render("soon}"), "soon}");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(render("{{phase}}"), "{phase}");
+        assert_eq!(render("{{{phase}}}"), "{Breakbar}");
+        assert_eq!(render("}}{{"), "}{");
+    }
+}
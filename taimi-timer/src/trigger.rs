@@ -0,0 +1,296 @@
+use {
+    super::TimerKeybinds,
+    crate::{BlishPosition, BlishVec3, Polytope, Position},
+    glam::Vec2,
+    serde::{Deserialize, Serialize},
+    strum_macros::{Display, EnumIter},
+    tokio::time::Duration,
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerTrigger {
+    #[serde(rename = "type", default)]
+    pub kind: TimerTriggerType,
+    #[serde(alias = "keyBinds")]
+    pub key_bind: Option<String>,
+    pub position: Option<BlishPosition>,
+    pub antipode: Option<BlishPosition>,
+    pub radius: Option<f32>,
+    /// Bounds a `radius` into an upright cylinder, or a `polygon` into a prism.
+    pub min_height: Option<f32>,
+    pub max_height: Option<f32>,
+    /// The corners of a polygon on the ground, as `[x, y]`.
+    pub polygon: Option<Vec<Vec2>>,
+    /// The full size of a box centred on `position`, turned by `yaw` degrees.
+    pub size: Option<BlishVec3>,
+    pub yaw: Option<f32>,
+    #[serde(default)]
+    pub require_combat: bool,
+    #[serde(default)]
+    pub require_out_of_combat: bool,
+    #[serde(default)]
+    pub require_entry: bool,
+    #[serde(default)]
+    pub require_departure: bool,
+    /// The skill cast, or the buff applied or removed.
+    #[serde(alias = "buffId")]
+    pub skill_id: Option<u32>,
+    /// Only match events about agents of this species.
+    pub species_id: Option<u32>,
+    /// Only match events about the player.
+    #[serde(default)]
+    pub on_self: bool,
+    /// Health percentage, at or below which a health threshold trigger fires.
+    pub threshold: Option<f32>,
+    pub breakbar: Option<BreakbarState>,
+    /// Seconds since the phase started, or since the previous phase finished.
+    pub elapsed: Option<f32>,
+    /// The sub-triggers of an `all`, `any` or `not` group.
+    #[serde(default)]
+    pub triggers: Vec<TimerTrigger>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CombatState {
+    #[default]
+    Outside,
+    Entered,
+    Exited,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum BreakbarState {
+    Active,
+    Recover,
+    Immune,
+    None,
+}
+
+/// Who an ArcDPS combat event was about.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerAgent {
+    pub species_id: Option<u32>,
+    pub is_self: bool,
+}
+
+/// The parts of the ArcDPS combat event stream that triggers can be driven by.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum CombatTriggerEvent {
+    SkillCast { skill_id: u32, agent: TriggerAgent },
+    BuffApply { skill_id: u32, agent: TriggerAgent },
+    BuffRemove { skill_id: u32, agent: TriggerAgent },
+    AgentSpawn { agent: TriggerAgent },
+    AgentDeath { agent: TriggerAgent },
+    Health { percent: f32, agent: TriggerAgent },
+    Breakbar { state: BreakbarState, agent: TriggerAgent },
+}
+
+impl CombatTriggerEvent {
+    pub fn agent(&self) -> &TriggerAgent {
+        use CombatTriggerEvent::*;
+        match self {
+            SkillCast { agent, .. }
+            | BuffApply { agent, .. }
+            | BuffRemove { agent, .. }
+            | AgentSpawn { agent }
+            | AgentDeath { agent }
+            | Health { agent, .. }
+            | Breakbar { agent, .. } => agent,
+        }
+    }
+}
+
+/**
+ * Everything a timer's triggers get to look at on a tick, besides the player's position.
+ * Combat events only last for the tick after they arrive.
+ */
+#[derive(Debug, Clone, Default)]
+pub struct TriggerInputs {
+    pub combat_state: CombatState,
+    pub key_pressed: TimerKeybinds,
+    pub combat_events: Vec<CombatTriggerEvent>,
+    /**
+     * How long the timer has been in its current state: since the phase started while
+     * within one, or since the previous phase finished while waiting for the next.
+     */
+    pub elapsed: Duration,
+}
+
+impl TimerTrigger {
+    #[allow(dead_code)]
+    pub fn position(&self) -> Option<Position> {
+        self.position.map(Into::into)
+    }
+
+    #[allow(dead_code)]
+    pub fn antipode(&self) -> Option<Position> {
+        self.antipode.map(Into::into)
+    }
+
+    /// The timer key a key trigger listens for, if `key_bind` names one that exists.
+    pub fn key_flag(&self) -> Option<TimerKeybinds> {
+        let idx = self.key_bind.as_deref()?.trim().parse::<u32>().ok()?;
+        TimerKeybinds::from_bits(1u8.checked_shl(idx)?)
+    }
+
+    /// Whether the trigger cannot do anything without a `polytope`.
+    pub fn needs_shape(&self) -> bool {
+        use TimerTriggerType::*;
+        matches!(self.kind, Location | Key) || self.require_entry || self.require_departure
+    }
+
+    pub fn polytope(&self) -> Option<Polytope> {
+        let height_bounded = self.min_height.is_some() || self.max_height.is_some();
+        match self {
+            Self {
+                polygon: Some(vertices),
+                ..
+            } if vertices.len() >= 3 => Some(Polytope::NPrism {
+                vertices: vertices.clone(),
+                min_height: self.min_height,
+                max_height: self.max_height,
+            }),
+            Self {
+                size: Some(size),
+                position: Some(BlishPosition::Vec3(center)),
+                ..
+            } => Some(Polytope::OrientedBox {
+                center: center.to_vec3(),
+                half_extents: size.to_vec3().abs() / 2.0,
+                yaw: self.yaw.unwrap_or_default().to_radians(),
+            }),
+            Self {
+                radius: Some(radius),
+                position: Some(center),
+                ..
+            } if height_bounded => Some(Polytope::NCylinder {
+                center: Position::from(*center).into(),
+                radius: *radius,
+                min_height: self.min_height,
+                max_height: self.max_height,
+            }),
+            Self {
+                radius: Some(radius),
+                position: Some(center),
+                ..
+            } => Some(Polytope::NSphere {
+                radius: *radius,
+                center: (*center).into(),
+            }),
+            Self {
+                antipode: Some(antipode),
+                position: Some(pode),
+                ..
+            } => Some(Polytope::NCuboid {
+                antipode: (*antipode).into(),
+                pode: (*pode).into(),
+            }),
+            _ => None,
+        }
+    }
+    fn agent_matches(&self, agent: &TriggerAgent) -> bool {
+        let self_check = !self.on_self || agent.is_self;
+        let species_check = self
+            .species_id
+            .is_none_or(|species_id| agent.species_id == Some(species_id));
+        self_check && species_check
+    }
+
+    fn combat_event_matches(&self, event: &CombatTriggerEvent) -> bool {
+        use {CombatTriggerEvent as Event, TimerTriggerType::*};
+        let skill_matches = |skill_id: &u32| self.skill_id == Some(*skill_id);
+        let kind_matches = match (&self.kind, event) {
+            (SkillCast, Event::SkillCast { skill_id, .. }) => skill_matches(skill_id),
+            (BuffApply, Event::BuffApply { skill_id, .. }) => skill_matches(skill_id),
+            (BuffRemove, Event::BuffRemove { skill_id, .. }) => skill_matches(skill_id),
+            (AgentSpawn, Event::AgentSpawn { .. }) => true,
+            (AgentDeath, Event::AgentDeath { .. }) => true,
+            (HealthThreshold, Event::Health { percent, .. }) => {
+                self.threshold.is_some_and(|threshold| *percent <= threshold)
+            }
+            (Breakbar, Event::Breakbar { state, .. }) => {
+                self.breakbar.is_none_or(|breakbar| breakbar == *state)
+            }
+            _ => false,
+        };
+        kind_matches && self.agent_matches(event.agent())
+    }
+
+    pub fn check(&self, pos: Position, inputs: &mut TriggerInputs) -> bool {
+        let shape = self.polytope();
+        use TimerTriggerType::*;
+        if shape.is_none() && matches!(self.kind, Location | Key) {
+            return false;
+        }
+        let mut group_inputs = None;
+        let event_check = match self.kind {
+            Location => true,
+            Key => {
+                if let Some(flaggy) = self.key_flag() {
+                    let result = inputs.key_pressed.contains(flaggy.clone());
+                    inputs.key_pressed.remove(flaggy);
+                    result
+                } else {
+                    false
+                }
+            }
+            SkillCast | BuffApply | BuffRemove | AgentSpawn | AgentDeath | HealthThreshold
+            | Breakbar => inputs
+                .combat_events
+                .iter()
+                .any(|event| self.combat_event_matches(event)),
+            Elapsed => self
+                .elapsed
+                .is_some_and(|elapsed| inputs.elapsed.as_secs_f32() >= elapsed),
+            All | Any | Not => {
+                // sub-triggers use up keys from a copy, kept only if the whole group fires
+                let group_inputs = group_inputs.insert(inputs.clone());
+                let sub_check = |trigger: &Self| trigger.check(pos, group_inputs);
+                match self.kind {
+                    All => self.triggers.iter().all(sub_check),
+                    Any => self.triggers.iter().any(sub_check),
+                    _ => !self.triggers.iter().any(sub_check),
+                }
+            }
+        };
+        // combat event triggers need not have a shape, but then cannot require entry
+        let position_check = shape.is_some_and(|shape| shape.point_is_within(pos));
+        let cb = inputs.combat_state;
+        let combat_entered_check = !self.require_combat || cb == CombatState::Entered;
+        let combat_exited_check = !self.require_out_of_combat || cb == CombatState::Exited;
+        let combat_check = combat_entered_check && combat_exited_check;
+        let entry_check = !self.require_entry || position_check;
+        let departure_check = !self.require_departure || !position_check;
+        let fired = entry_check && departure_check && combat_check && event_check;
+        if let Some(group_inputs) = group_inputs.filter(|_| fired) {
+            inputs.key_pressed = group_inputs.key_pressed;
+        }
+        fired
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Default, Display, EnumIter)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "camelCase")]
+pub enum TimerTriggerType {
+    #[default]
+    Location,
+    Key,
+    SkillCast,
+    BuffApply,
+    BuffRemove,
+    AgentSpawn,
+    AgentDeath,
+    HealthThreshold,
+    Breakbar,
+    Elapsed,
+    All,
+    Any,
+    /// Fires while none of its sub-triggers would.
+    Not,
+}